@@ -103,21 +103,11 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         books: Vec<ResBook>,
     }
 
-    let res = User::aggregate(vec![
-        doc! {
-            "$match": {
-                "email": "john@example.com"
-            }
-        },
-        doc! {
-            "$lookup": {
-                "from": "books",
-                "localField": "_id",
-                "foreignField": "user_id",
-                "as": "books"
-            }
-        },
-        doc! {
+    let res = User::dsl
+        .aggregate()
+        .match_(User::EMAIL.eq("john@example.com"))
+        .lookup(User::ID, Book::USER_ID, "books")
+        .stage(doc! {
             "$lookup": {
                 "from": "stores",
                 "let": { "books": "$books" },
@@ -138,8 +128,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 ],
                 "as": "stores"
             }
-        },
-        doc! {
+        })
+        .stage(doc! {
             "$project": {
                 "_id": 1,
                 "name": 1,
@@ -153,13 +143,15 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                             "author": "$$book.author",
                             "user_id": "$$book.user_id",
                             "store_id": "$$book.store_id",
-                            // filter store by id
+                            // pick the store of the book
                             "store": {
-                                "$filter": {
-                                    "input": "$stores",
-                                    "as": "store",
-                                    "cond": {
-                                        "$eq": ["$$store._id", "$$book.store_id"]
+                                "$first": {
+                                    "$filter": {
+                                        "input": "$stores",
+                                        "as": "store",
+                                        "cond": {
+                                            "$eq": ["$$store._id", "$$book.store_id"]
+                                        }
                                     }
                                 }
                             }
@@ -167,9 +159,9 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                     }
                 }
             }
-        }
-    ])
-    .await?;
+        })
+        .into::<Res>()
+        .await?;
 
     println!("{:#?}", res);
     clean().await?;
@@ -0,0 +1,95 @@
+use crate::serde_attrs::{SerdeContainer, SerdeField};
+use change_case::{snake_case, upper_case};
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{ext::IdentExt, Data, DeriveInput, Fields, Ident};
+
+/// Get token representation of the field markers of an embedded struct
+fn gen(input: DeriveInput) -> syn::Result<TokenStream> {
+    if !input.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(
+            input.generics,
+            "Generics are not supported",
+        ));
+    }
+
+    let named_fields = match &input.data {
+        Data::Struct(dt) => match &dt.fields {
+            Fields::Named(fields) => &fields.named,
+            fields => {
+                return Err(syn::Error::new_spanned(
+                    fields,
+                    "Only named fields are supported",
+                ))
+            }
+        },
+        _ => {
+            return Err(syn::Error::new_spanned(
+                &input.ident,
+                "Only structs are supported",
+            ))
+        }
+    };
+
+    let ident = &input.ident;
+    let vis = &input.vis;
+    let mod_ident = Ident::new(&snake_case(&ident.to_string()), ident.span());
+    let container = SerdeContainer::parse(&input.attrs)?;
+    let mut markers = quote! {};
+    let mut consts = quote! {};
+
+    for field in named_fields {
+        let field_ident = field.ident.as_ref().expect("named field");
+        let serde = SerdeField::parse_field(field_ident, &field.attrs, &container)?;
+        // skipped and flattened fields have no key of their own
+        if serde.skip || serde.flatten {
+            continue;
+        }
+
+        let field_ty = &field.ty;
+        let field_name = &serde.name;
+        let const_field_ident = Ident::new(
+            &upper_case(&field_ident.unraw().to_string()),
+            field_ident.span(),
+        );
+        markers.extend(quote! {
+            #[derive(Debug, Clone, Copy)]
+            pub struct #field_ident;
+            impl EmbeddedField for #field_ident {
+                type Parent = #ident;
+                const NAME: &'static str = #field_name;
+                type Type = #field_ty;
+            }
+        });
+        consts.extend(quote! {
+            #vis const #const_field_ident: #mod_ident::#field_ident = #mod_ident::#field_ident;
+        });
+    }
+
+    Ok(quote! {
+        const _: () = {
+            #[allow(unused_imports)]
+            use ::mangga::prelude::*;
+            #[allow(non_camel_case_types, dead_code)]
+            mod #mod_ident {
+                use super::*;
+                #markers
+            }
+            impl Embedded for #ident {
+                type Inner = Self;
+            }
+            impl #ident {
+                #consts
+            }
+        };
+    })
+}
+
+pub fn parse(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = syn::parse_macro_input!(input as DeriveInput);
+
+    match gen(input) {
+        Ok(tokens) => tokens.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
@@ -1,6 +1,25 @@
+mod embedded;
 mod model;
+mod projection;
+mod schema;
+mod serde_attrs;
 
 #[proc_macro_derive(Model, attributes(mangga, index, graphql))]
 pub fn model(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     model::parse(input)
 }
+
+#[proc_macro_derive(Projection, attributes(projection))]
+pub fn projection(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    projection::parse(input)
+}
+
+#[proc_macro_derive(BsonSchema)]
+pub fn bson_schema(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    schema::parse(input)
+}
+
+#[proc_macro_derive(Embedded)]
+pub fn embedded(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    embedded::parse(input)
+}
@@ -1,7 +1,12 @@
+use super::{
+    collection::CollectionDef,
+    fields::ItemFields,
+    index::{IndexDef, IndexExtra, IndexKind},
+};
 use proc_macro2::TokenStream;
 use quote::quote;
 use std::collections::HashSet;
-use syn::parse::Parse;
+use syn::{ext::IdentExt, parse::Parse};
 
 /// ItemAttrs
 ///
@@ -10,6 +15,10 @@ use syn::parse::Parse;
 pub struct ItemAttrs {
     pub name: String,
     pub db_name: String,
+    pub connection: Option<String>,
+    pub drop_unknown: Option<bool>,
+    pub dry_run: Option<bool>,
+    pub collection: CollectionDef,
 }
 
 impl Parse for ItemAttrs {
@@ -18,10 +27,13 @@ impl Parse for ItemAttrs {
         let span = input.span();
         let mut name = String::new();
         let mut db_name = String::new();
+        let mut connection = None;
+        let mut drop_unknown = None;
+        let mut dry_run = None;
+        let mut collection = CollectionDef::default();
 
         while !input.is_empty() {
             let id = input.parse::<syn::Ident>()?;
-            input.parse::<syn::Token![=]>()?;
             let id_str = id.to_string();
             if keys.contains(&id_str) {
                 return Err(syn::Error::new_spanned(
@@ -32,9 +44,30 @@ impl Parse for ItemAttrs {
 
             keys.insert(id_str.clone());
 
+            if collection.parse_option(&id, input)? {
+                if !input.is_empty() {
+                    input.parse::<syn::Token![,]>()?;
+                }
+                continue;
+            }
+
+            // `dry_run` may be given without a value
+            let has_value = id_str != "dry_run" || input.peek(syn::Token![=]);
+            if has_value {
+                input.parse::<syn::Token![=]>()?;
+            }
+
             match &*id_str {
                 "name" => name = input.parse::<syn::LitStr>()?.value(),
                 "db" => db_name = input.parse::<syn::LitStr>()?.value(),
+                "connection" => connection = Some(input.parse::<syn::LitStr>()?.value()),
+                "drop_unknown" => drop_unknown = Some(input.parse::<syn::LitBool>()?.value()),
+                "dry_run" => {
+                    dry_run = Some(match has_value {
+                        true => input.parse::<syn::LitBool>()?.value(),
+                        false => true,
+                    })
+                }
                 _ => {
                     return Err(syn::Error::new_spanned(
                         id,
@@ -52,7 +85,14 @@ impl Parse for ItemAttrs {
             return Err(syn::Error::new(span, "name and db attributes are required"));
         }
 
-        Ok(ItemAttrs { name, db_name })
+        Ok(ItemAttrs {
+            name,
+            db_name,
+            connection,
+            drop_unknown,
+            dry_run,
+            collection,
+        })
     }
 }
 
@@ -112,3 +152,160 @@ impl Parse for ItemGraphql {
         })
     }
 }
+
+/// ItemIndex
+///
+/// Represents an index declared on a struct
+#[derive(Debug, Clone)]
+pub struct ItemIndex {
+    pub name: Option<String>,
+    pub keys: Vec<(syn::Ident, IndexKind)>,
+    pub wildcard: bool,
+    pub unique: bool,
+    pub exp: Option<u64>,
+    pub weights: Vec<(syn::Ident, i32)>,
+    pub default_language: Option<String>,
+    pub extra: IndexExtra,
+    pub span: proc_macro2::Span,
+}
+
+impl ItemIndex {
+    /// Get the index definition with the field names resolved
+    pub fn def(&self, fields: &ItemFields) -> syn::Result<IndexDef> {
+        let field_name = |ident: &syn::Ident| fields.stored(ident).map(|f| f.name.clone());
+
+        let mut keys = self
+            .keys
+            .iter()
+            .map(|(ident, kind)| Ok((field_name(ident)?, *kind)))
+            .collect::<syn::Result<Vec<_>>>()?;
+        if self.wildcard {
+            keys.push(("$**".to_string(), IndexKind::Wildcard));
+        }
+        let mut const_fields = self
+            .keys
+            .iter()
+            .map(|(ident, kind)| (ident.unraw().to_string(), *kind))
+            .collect::<Vec<_>>();
+        if self.wildcard {
+            const_fields.push(("all".to_string(), IndexKind::Wildcard));
+        }
+        let weights = self
+            .weights
+            .iter()
+            .map(|(ident, weight)| Ok((field_name(ident)?, *weight)))
+            .collect::<syn::Result<Vec<_>>>()?;
+
+        let def = IndexDef {
+            name: self.name.clone(),
+            keys,
+            unique: self.unique,
+            exp: self.exp,
+            weights,
+            default_language: self.default_language.clone(),
+            extra: self.extra.clone(),
+            span: self.span,
+            const_name: IndexDef::const_name(self.name.as_deref(), &const_fields),
+        };
+        def.validate()?;
+        Ok(def)
+    }
+}
+
+impl Parse for ItemIndex {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let mut keys_set: HashSet<String> = HashSet::new();
+        let span = input.span();
+        let mut name = None;
+        let mut keys = vec![];
+        let mut wildcard = false;
+        let mut unique = false;
+        let mut exp = None;
+        let mut weights = vec![];
+        let mut default_language = None;
+        let mut extra = IndexExtra::default();
+
+        while !input.is_empty() {
+            let id = input.parse::<syn::Ident>()?;
+            let id_str = id.to_string();
+            if keys_set.contains(&id_str) {
+                return Err(syn::Error::new_spanned(
+                    id,
+                    format!("duplicate attribute `{}`", id_str),
+                ));
+            }
+
+            keys_set.insert(id_str.clone());
+
+            match &*id_str {
+                "fields" => {
+                    let stream;
+                    syn::parenthesized!(stream in input);
+                    while !stream.is_empty() {
+                        let field = stream.parse::<syn::Ident>()?;
+                        stream.parse::<syn::Token![=]>()?;
+                        let kind = IndexKind::parse(&stream)?;
+                        if kind == IndexKind::Wildcard {
+                            return Err(syn::Error::new_spanned(
+                                field,
+                                "use `kind = \"wildcard\"` on the field or `wildcard` on the \
+                                 struct",
+                            ));
+                        }
+                        keys.push((field, kind));
+                        if !stream.is_empty() {
+                            stream.parse::<syn::Token![,]>()?;
+                        }
+                    }
+                }
+                "weights" => {
+                    let stream;
+                    syn::parenthesized!(stream in input);
+                    while !stream.is_empty() {
+                        let field = stream.parse::<syn::Ident>()?;
+                        stream.parse::<syn::Token![=]>()?;
+                        weights.push((field, stream.parse::<syn::LitInt>()?.base10_parse()?));
+                        if !stream.is_empty() {
+                            stream.parse::<syn::Token![,]>()?;
+                        }
+                    }
+                }
+                "wildcard" => wildcard = true,
+                _ if extra.parse_option(&id, input)? => {}
+                _ => {
+                    input.parse::<syn::Token![=]>()?;
+                    match &*id_str {
+                        "name" => name = Some(input.parse::<syn::LitStr>()?.value()),
+                        "unique" => unique = input.parse::<syn::LitBool>()?.value(),
+                        "exp" => exp = Some(input.parse::<syn::LitInt>()?.base10_parse()?),
+                        "default_language" => {
+                            default_language = Some(input.parse::<syn::LitStr>()?.value())
+                        }
+                        _ => {
+                            return Err(syn::Error::new_spanned(
+                                id,
+                                format!("unknown attribute `{}`", id_str),
+                            ))
+                        }
+                    }
+                }
+            }
+
+            if !input.is_empty() {
+                input.parse::<syn::Token![,]>()?;
+            }
+        }
+
+        Ok(ItemIndex {
+            name,
+            keys,
+            wildcard,
+            unique,
+            exp,
+            weights,
+            default_language,
+            extra,
+            span,
+        })
+    }
+}
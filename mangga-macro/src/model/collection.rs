@@ -0,0 +1,322 @@
+use super::fields::ItemFields;
+use proc_macro2::{Span, TokenStream};
+use quote::{quote, quote_spanned};
+use syn::{parse::Parse, spanned::Spanned};
+
+/// CappedDef
+///
+/// Represents the `capped(...)` collection attribute
+#[derive(Debug, Clone)]
+pub struct CappedDef {
+    pub size: u64,
+    pub max: Option<u64>,
+    pub span: Span,
+}
+
+impl Parse for CappedDef {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let span = input.span();
+        let mut size = None;
+        let mut max = None;
+
+        while !input.is_empty() {
+            let id = input.parse::<syn::Ident>()?;
+            let id_str = id.to_string();
+            input.parse::<syn::Token![=]>()?;
+            match &*id_str {
+                "size" => {
+                    let lit = input.parse::<syn::LitInt>()?;
+                    let value = lit.base10_parse::<u64>()?;
+                    if value == 0 {
+                        return Err(syn::Error::new_spanned(lit, "size must be greater than 0"));
+                    }
+                    size = Some(value);
+                }
+                "max" => max = Some(input.parse::<syn::LitInt>()?.base10_parse()?),
+                _ => {
+                    return Err(syn::Error::new_spanned(
+                        id,
+                        format!("unknown attribute `{}`", id_str),
+                    ))
+                }
+            }
+
+            if !input.is_empty() {
+                input.parse::<syn::Token![,]>()?;
+            }
+        }
+
+        let size = size.ok_or_else(|| syn::Error::new(span, "size is required"))?;
+        Ok(CappedDef { size, max, span })
+    }
+}
+
+/// TimeseriesDef
+///
+/// Represents the `timeseries(...)` collection attribute
+#[derive(Debug, Clone)]
+pub struct TimeseriesDef {
+    pub time_field: syn::Ident,
+    pub meta_field: Option<syn::Ident>,
+    pub granularity: Option<String>,
+    pub span: Span,
+}
+
+impl Parse for TimeseriesDef {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let span = input.span();
+        let mut time_field = None;
+        let mut meta_field = None;
+        let mut granularity = None;
+
+        while !input.is_empty() {
+            let id = input.parse::<syn::Ident>()?;
+            let id_str = id.to_string();
+            input.parse::<syn::Token![=]>()?;
+            match &*id_str {
+                "time_field" => time_field = Some(input.parse::<syn::Ident>()?),
+                "meta_field" => meta_field = Some(input.parse::<syn::Ident>()?),
+                "granularity" => {
+                    let lit = input.parse::<syn::LitStr>()?;
+                    let allowed = ["seconds", "minutes", "hours"];
+                    if !allowed.contains(&lit.value().as_str()) {
+                        return Err(syn::Error::new_spanned(
+                            &lit,
+                            format!("allowed values are: {}", allowed.join(", ")),
+                        ));
+                    }
+                    granularity = Some(lit.value());
+                }
+                _ => {
+                    return Err(syn::Error::new_spanned(
+                        id,
+                        format!("unknown attribute `{}`", id_str),
+                    ))
+                }
+            }
+
+            if !input.is_empty() {
+                input.parse::<syn::Token![,]>()?;
+            }
+        }
+
+        let time_field =
+            time_field.ok_or_else(|| syn::Error::new(span, "time_field is required"))?;
+        Ok(TimeseriesDef {
+            time_field,
+            meta_field,
+            granularity,
+            span,
+        })
+    }
+}
+
+/// SchemaDef
+///
+/// Represents the `schema(...)` collection attribute
+#[derive(Debug, Clone, Default)]
+pub struct SchemaDef {
+    pub level: Option<String>,
+    pub action: Option<String>,
+}
+
+impl Parse for SchemaDef {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let mut def = SchemaDef::default();
+        let one_of = |lit: syn::LitStr, allowed: &[&str]| {
+            if allowed.contains(&lit.value().as_str()) {
+                Ok(lit.value())
+            } else {
+                Err(syn::Error::new_spanned(
+                    &lit,
+                    format!("allowed values are: {}", allowed.join(", ")),
+                ))
+            }
+        };
+
+        while !input.is_empty() {
+            let id = input.parse::<syn::Ident>()?;
+            let id_str = id.to_string();
+            input.parse::<syn::Token![=]>()?;
+            match &*id_str {
+                "level" => {
+                    def.level = Some(one_of(input.parse()?, &["off", "strict", "moderate"])?)
+                }
+                "action" => def.action = Some(one_of(input.parse()?, &["error", "warn"])?),
+                _ => {
+                    return Err(syn::Error::new_spanned(
+                        id,
+                        format!("unknown attribute `{}`", id_str),
+                    ))
+                }
+            }
+
+            if !input.is_empty() {
+                input.parse::<syn::Token![,]>()?;
+            }
+        }
+
+        Ok(def)
+    }
+}
+
+/// CollectionDef
+///
+/// Represents the collection options of a struct
+#[derive(Debug, Clone, Default)]
+pub struct CollectionDef {
+    pub capped: Option<CappedDef>,
+    pub timeseries: Option<TimeseriesDef>,
+    pub clustered: Option<Span>,
+    pub expire_after: Option<syn::LitInt>,
+    pub schema: Option<SchemaDef>,
+}
+
+impl CollectionDef {
+    /// Parse the option of the ident, returns false when the ident is not a collection option
+    pub fn parse_option(
+        &mut self,
+        id: &syn::Ident,
+        input: syn::parse::ParseStream,
+    ) -> syn::Result<bool> {
+        match &*id.to_string() {
+            "capped" => {
+                let stream;
+                syn::parenthesized!(stream in input);
+                self.capped = Some(stream.parse()?);
+            }
+            "timeseries" => {
+                let stream;
+                syn::parenthesized!(stream in input);
+                self.timeseries = Some(stream.parse()?);
+            }
+            "clustered" => self.clustered = Some(id.span()),
+            "schema" => {
+                self.schema = Some(match input.peek(syn::token::Paren) {
+                    true => {
+                        let stream;
+                        syn::parenthesized!(stream in input);
+                        stream.parse()?
+                    }
+                    false => SchemaDef::default(),
+                })
+            }
+            "expire_after" => {
+                input.parse::<syn::Token![=]>()?;
+                let lit = input.parse::<syn::LitInt>()?;
+                lit.base10_parse::<u64>()?;
+                self.expire_after = Some(lit);
+            }
+            _ => return Ok(false),
+        }
+
+        Ok(true)
+    }
+
+    /// Validate the options against the fields and get token representation
+    pub fn gen(&self, fields: &ItemFields) -> syn::Result<Option<TokenStream>> {
+        if self.capped.is_none()
+            && self.timeseries.is_none()
+            && self.clustered.is_none()
+            && self.expire_after.is_none()
+            && self.schema.is_none()
+        {
+            return Ok(None);
+        }
+        if let Some(capped) = &self.capped {
+            if self.timeseries.is_some() || self.clustered.is_some() || self.expire_after.is_some()
+            {
+                return Err(syn::Error::new(
+                    capped.span,
+                    "capped cannot be combined with timeseries, clustered or expire_after",
+                ));
+            }
+        }
+        if let (Some(timeseries), Some(_)) = (&self.timeseries, self.clustered) {
+            return Err(syn::Error::new(
+                timeseries.span,
+                "timeseries collections are already clustered",
+            ));
+        }
+        if let Some(exp) = &self.expire_after {
+            if self.timeseries.is_none() && self.clustered.is_none() {
+                return Err(syn::Error::new_spanned(
+                    exp,
+                    "expire_after requires timeseries or clustered",
+                ));
+            }
+        }
+
+        let capped = match &self.capped {
+            Some(CappedDef { size, max, .. }) => {
+                let max = match max {
+                    Some(max) => quote! {Some(#max)},
+                    None => quote! {None},
+                };
+                quote! {Some(CappedSpec { size: #size, max: #max })}
+            }
+            None => quote! {None},
+        };
+        let timeseries = match &self.timeseries {
+            Some(timeseries) => {
+                let field = |ident: &syn::Ident| fields.stored(ident);
+                let time_field = field(&timeseries.time_field)?;
+                let time_name = &time_field.name;
+                let meta_field = match &timeseries.meta_field {
+                    Some(ident) if ident == &timeseries.time_field => {
+                        return Err(syn::Error::new_spanned(
+                            ident,
+                            "meta_field must differ from time_field",
+                        ))
+                    }
+                    Some(ident) => {
+                        let name = &field(ident)?.name;
+                        quote! {Some(#name)}
+                    }
+                    None => quote! {None},
+                };
+                let granularity = match &timeseries.granularity {
+                    Some(granularity) => quote! {Some(#granularity)},
+                    None => quote! {None},
+                };
+                // the compiler checks the time field is stored as bson date
+                let time_ty = &time_field.ty;
+                let spec = quote_spanned! {time_ty.span()=>
+                    TimeseriesSpec::new::<#time_ty>(#time_name)
+                };
+                quote! {
+                    Some(TimeseriesSpec {
+                        meta_field: #meta_field,
+                        granularity: #granularity,
+                        ..#spec
+                    })
+                }
+            }
+            None => quote! {None},
+        };
+        let clustered = self.clustered.is_some();
+        let expire_after = match &self.expire_after {
+            Some(exp) => quote! {Some(#exp)},
+            None => quote! {None},
+        };
+
+        let schema = self.schema.clone().unwrap_or_default();
+        let opt = |value: Option<String>| match value {
+            Some(value) => quote! {Some(#value)},
+            None => quote! {None},
+        };
+        let validation_level = opt(schema.level);
+        let validation_action = opt(schema.action);
+
+        Ok(Some(quote! {
+            const COLLECTION: CollectionSpec = CollectionSpec {
+                capped: #capped,
+                timeseries: #timeseries,
+                clustered: #clustered,
+                expire_after: #expire_after,
+                validation_level: #validation_level,
+                validation_action: #validation_action,
+            };
+        }))
+    }
+}
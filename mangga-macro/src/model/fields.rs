@@ -1,7 +1,18 @@
+use super::index::{IndexDef, IndexExtra, IndexKind};
+use crate::serde_attrs::{SerdeContainer, SerdeField};
 use change_case::upper_case;
 use proc_macro2::TokenStream;
 use quote::{quote, ToTokens};
-use syn::{parse::Parse, punctuated::Punctuated, spanned::Spanned, Fields, Ident, Token};
+use std::collections::HashSet;
+use syn::{
+    ext::IdentExt,
+    parse::Parse,
+    punctuated::Punctuated,
+    spanned::Spanned,
+    Fields,
+    Ident,
+    Token,
+};
 
 /// FieldIndex
 ///
@@ -12,48 +23,78 @@ pub struct FieldIndex {
     pub unique: bool,
     pub score: i32,
     pub exp: Option<u64>,
+    pub kind: Option<IndexKind>,
+    pub default_language: Option<String>,
+    pub extra: IndexExtra,
+    pub span: proc_macro2::Span,
 }
 
 impl FieldIndex {
-    /// Get token representation
-    pub fn gen(&self, field: &Ident) -> TokenStream {
-        let score = self.score;
-        let unique = self.unique;
-        let exp = &self.exp;
-        let name = if let Some(name) = &self.name {
-            name.to_owned()
-        } else {
-            let exp = if exp.is_some() { "exp" } else { "no-exp" };
-            let unique = if unique { "unique" } else { "no-unique" };
-            format!("mangga_index_{}_{}_{}_{}", field, score, unique, exp)
+    /// Get the index definition of the field
+    pub fn def(&self, field: &str, ident: &Ident) -> syn::Result<IndexDef> {
+        let kind = match (self.kind, self.score) {
+            (Some(kind), _) => kind,
+            (None, 1) => IndexKind::Asc,
+            (None, -1) => IndexKind::Desc,
+            _ => return Err(syn::Error::new(self.span, "score must be 1 or -1")),
         };
-        let field_str = field.to_string();
-        let exp = if let Some(exp) = exp {
-            quote! {Some(#exp)}
+        let key = if kind == IndexKind::Wildcard {
+            format!("{}.$**", field)
         } else {
-            quote! {None}
+            field.to_string()
         };
-
-        quote! { (#field_str, #name, #score, #unique, #exp) }
+        let def = IndexDef {
+            name: self.name.clone(),
+            keys: vec![(key, kind)],
+            unique: self.unique,
+            exp: self.exp,
+            weights: vec![],
+            default_language: self.default_language.clone(),
+            extra: self.extra.clone(),
+            span: self.span,
+            const_name: IndexDef::const_name(
+                self.name.as_deref(),
+                &[(ident.unraw().to_string(), kind)],
+            ),
+        };
+        def.validate()?;
+        Ok(def)
     }
 }
 
 impl Parse for FieldIndex {
     fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let span = input.span();
         let mut name = None;
         let mut unique = false;
-        let mut score = 1;
+        let mut score = None;
         let mut exp = None;
+        let mut kind = None;
+        let mut default_language = None;
+        let mut extra = IndexExtra::default();
 
         while !input.is_empty() {
             let id = input.parse::<syn::Ident>()?;
             let id_str = id.to_string();
+            if extra.parse_option(&id, input)? {
+                if !input.is_empty() {
+                    input.parse::<Token![,]>()?;
+                }
+                continue;
+            }
             input.parse::<syn::Token![=]>()?;
             match &*id_str {
                 "name" => name = Some(input.parse::<syn::LitStr>()?.value()),
                 "unique" => unique = input.parse::<syn::LitBool>()?.value(),
                 "exp" => exp = Some(input.parse::<syn::LitInt>()?.base10_parse()?),
-                "score" => score = input.parse::<syn::LitInt>()?.base10_parse()?,
+                "score" => score = Some(input.parse::<syn::LitInt>()?),
+                "kind" => {
+                    let lit = input.parse::<syn::LitStr>()?;
+                    kind = Some(IndexKind::from_name(&lit.value(), lit.span())?);
+                }
+                "default_language" => {
+                    default_language = Some(input.parse::<syn::LitStr>()?.value())
+                }
                 _ => {
                     return Err(syn::Error::new_spanned(
                         id,
@@ -67,11 +108,27 @@ impl Parse for FieldIndex {
             }
         }
 
+        // the kind decides the direction of the key, a score would be ignored
+        if let (Some(score), Some(_)) = (&score, kind) {
+            return Err(syn::Error::new_spanned(
+                score,
+                "`score` can not be used together with `kind`",
+            ));
+        }
+        let score = match score {
+            Some(score) => score.base10_parse()?,
+            None => 1,
+        };
+
         Ok(FieldIndex {
             name,
             unique,
             exp,
             score,
+            kind,
+            default_language,
+            extra,
+            span,
         })
     }
 }
@@ -99,7 +156,7 @@ impl Parse for GraphqlRel {
         let mut ty = String::from("default");
         let mut field = Ident::new("id", span);
         let mut check_fn = None;
-        let allowed_ty = vec!["array", "option", "default", "opt-array"];
+        let allowed_ty = ["array", "option", "default", "opt-array"];
 
         while !stream.is_empty() {
             let id = stream.parse::<syn::Ident>()?;
@@ -208,6 +265,14 @@ pub struct ItemField {
     pub ty: syn::Type,
     pub attrs: FieldAttr,
     pub vis: syn::Visibility,
+    pub serde: SerdeField,
+}
+
+impl ItemField {
+    /// Check whether the field is stored under its own key
+    pub fn is_stored(&self) -> bool {
+        !self.serde.skip && !self.serde.flatten
+    }
 }
 
 /// ItemFields
@@ -221,7 +286,7 @@ pub struct ItemFields {
 
 impl ItemFields {
     /// Parse the fields of a struct
-    pub fn parse(input: Fields) -> syn::Result<Self> {
+    pub fn parse(input: Fields, attrs: &[syn::Attribute]) -> syn::Result<Self> {
         let span = input.span();
         let named_fields = match input {
             Fields::Named(fields) => fields.named,
@@ -233,7 +298,8 @@ impl ItemFields {
             }
         };
 
-        let mut id_found = false;
+        let container = SerdeContainer::parse(attrs)?;
+        let mut names = HashSet::new();
         let mut id_field = None;
         let mut fields = Punctuated::<ItemField, Token![,]>::new();
 
@@ -266,56 +332,67 @@ impl ItemFields {
             let graphql = syn::parse2(graphql_tokens.to_token_stream())?;
             let field_attr = FieldAttr { indexes, graphql };
 
-            let mut item_field = ItemField {
-                name: ident.to_string(),
+            let serde = SerdeField::parse_field(ident, &field.attrs, &container)?;
+            if (serde.skip || serde.flatten) && !field_attr.indexes.is_empty() {
+                return Err(syn::Error::new_spanned(
+                    ident,
+                    "skipped or flattened fields cannot be indexed",
+                ));
+            }
+
+            let item_field = ItemField {
+                name: serde.name.clone(),
                 ident: ident.clone(),
                 ty: field.ty.clone(),
                 vis: field.vis.clone(),
                 attrs: field_attr,
+                serde,
             };
 
-            // check if field is _id or has #[serde(rename = "_id")]
-            if !id_found {
-                if ident == "_id" {
-                    id_found = true;
-                } else {
-                    let serde = field
-                        .attrs
-                        .iter()
-                        .filter(|attr| attr.path().is_ident("serde"))
-                        .collect::<Vec<_>>();
-                    for attr in serde {
-                        let list = match attr.meta.require_list() {
-                            Ok(list) => list,
-                            Err(_) => continue,
-                        };
-
-                        let list = list.tokens.to_string().replace(" ", "");
-                        if list.contains("rename=\"_id\"") {
-                            item_field.name = "_id".to_string();
-                            id_found = true;
-                            id_field = Some(item_field.clone());
-                            break;
-                        }
-                    }
+            if item_field.is_stored() {
+                if !names.insert(item_field.name.clone()) {
+                    return Err(syn::Error::new_spanned(
+                        ident,
+                        format!("duplicate key `{}`", item_field.name),
+                    ));
+                }
+                if item_field.name == "_id" {
+                    id_field = Some(item_field.clone());
                 }
+            } else if item_field.serde.skip && item_field.name == "_id" {
+                return Err(syn::Error::new_spanned(
+                    ident,
+                    "the `_id` field cannot be skipped",
+                ));
             }
 
             fields.push(item_field);
         }
 
-        if !id_found || id_field.is_none() {
-            return Err(syn::Error::new(
+        let id_field = id_field.ok_or_else(|| {
+            syn::Error::new(
                 span,
                 "No field for `id` is found. Add `_id` field or use `#[serde(rename = \"_id\")]` \
                  attribute.",
-            ));
-        }
+            )
+        })?;
 
-        Ok(Self {
-            fields,
-            id_field: id_field.unwrap(),
-        })
+        Ok(Self { fields, id_field })
+    }
+
+    /// Get the stored field of the ident
+    pub fn stored(&self, ident: &syn::Ident) -> syn::Result<&ItemField> {
+        match self.fields.iter().find(|f| &f.ident == ident) {
+            Some(field) if field.is_stored() => Ok(field),
+            Some(_) => Err(syn::Error::new_spanned(
+                ident,
+                format!("field `{}` is skipped or flattened", ident),
+            )),
+            None => Err(syn::Error::new_spanned(
+                ident,
+                format!("unknown field `{}`", ident),
+            )),
+        }
     }
 
     /// Generate code to check type of id field
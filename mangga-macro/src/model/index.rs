@@ -0,0 +1,422 @@
+use change_case::upper_case;
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use syn::parse::Parse;
+
+/// IndexKind
+///
+/// Represents the kind of an index key
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IndexKind {
+    Asc,
+    Desc,
+    Text,
+    Hashed,
+    Sphere2d,
+    Wildcard,
+}
+
+impl IndexKind {
+    /// Parse the kind from `1`, `-1` or the kind name
+    pub fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        if input.peek(syn::LitStr) {
+            let lit = input.parse::<syn::LitStr>()?;
+            Self::from_name(&lit.value(), lit.span())
+        } else {
+            let lit = input.parse::<syn::Expr>()?;
+            match quote!(#lit).to_string().replace(' ', "").as_str() {
+                "1" => Ok(IndexKind::Asc),
+                "-1" => Ok(IndexKind::Desc),
+                _ => Err(syn::Error::new_spanned(
+                    lit,
+                    "index key must be 1, -1, \"text\", \"hashed\" or \"2dsphere\"",
+                )),
+            }
+        }
+    }
+
+    /// Get the kind from its name
+    pub fn from_name(name: &str, span: Span) -> syn::Result<Self> {
+        match name {
+            "text" => Ok(IndexKind::Text),
+            "hashed" => Ok(IndexKind::Hashed),
+            "2dsphere" => Ok(IndexKind::Sphere2d),
+            "wildcard" => Ok(IndexKind::Wildcard),
+            _ => Err(syn::Error::new(
+                span,
+                "index kind must be \"text\", \"hashed\", \"2dsphere\" or \"wildcard\"",
+            )),
+        }
+    }
+
+    /// Get the name used in the generated index name
+    pub fn label(&self) -> &'static str {
+        match self {
+            IndexKind::Asc => "1",
+            IndexKind::Desc => "-1",
+            IndexKind::Text => "text",
+            IndexKind::Hashed => "hashed",
+            IndexKind::Sphere2d => "2dsphere",
+            IndexKind::Wildcard => "wildcard",
+        }
+    }
+
+    /// Get the name used in the index constant
+    pub fn const_label(&self) -> Option<&'static str> {
+        match self {
+            IndexKind::Asc => None,
+            IndexKind::Desc => Some("DESC"),
+            IndexKind::Text => Some("TEXT"),
+            IndexKind::Hashed => Some("HASHED"),
+            IndexKind::Sphere2d => Some("2DSPHERE"),
+            IndexKind::Wildcard => Some("WILDCARD"),
+        }
+    }
+}
+
+/// IndexDef
+///
+/// Represents a resolved index definition
+#[derive(Debug, Clone)]
+pub struct IndexDef {
+    pub name: Option<String>,
+    pub keys: Vec<(String, IndexKind)>,
+    pub unique: bool,
+    pub exp: Option<u64>,
+    pub weights: Vec<(String, i32)>,
+    pub default_language: Option<String>,
+    pub extra: IndexExtra,
+    pub span: Span,
+    /// Name of the generated index constant
+    pub const_name: String,
+}
+
+impl IndexDef {
+    /// Get the name of the index constant from the explicit index name or the key fields
+    pub fn const_name(name: Option<&str>, fields: &[(String, IndexKind)]) -> String {
+        let label = match name {
+            Some(name) => name
+                .chars()
+                .map(|c| match c.is_ascii_alphanumeric() {
+                    true => c.to_ascii_uppercase(),
+                    false => '_',
+                })
+                .collect::<String>(),
+            None => fields
+                .iter()
+                .map(|(field, kind)| match kind.const_label() {
+                    Some(label) => format!("{}_{}", upper_case(field), label),
+                    None => upper_case(field),
+                })
+                .collect::<Vec<_>>()
+                .join("_"),
+        };
+        format!("INDEX_{}", label)
+    }
+
+    /// Get the name of the index
+    pub fn name(&self) -> String {
+        if let Some(name) = &self.name {
+            return name.to_owned();
+        }
+
+        let keys = self
+            .keys
+            .iter()
+            .map(|(key, kind)| {
+                let key = key.replace(".$**", "").replace("$**", "all");
+                format!("{}_{}", key, kind.label())
+            })
+            .collect::<Vec<_>>()
+            .join("_");
+        let exp = if self.exp.is_some() { "exp" } else { "no-exp" };
+        let unique = if self.unique { "unique" } else { "no-unique" };
+        format!("mangga_index_{}_{}_{}", keys, unique, exp)
+    }
+
+    /// Check the combination of the options
+    pub fn validate(&self) -> syn::Result<()> {
+        let err = |msg: &str| Err(syn::Error::new(self.span, msg));
+        let plain = self
+            .keys
+            .iter()
+            .all(|(_, kind)| matches!(kind, IndexKind::Asc | IndexKind::Desc));
+        let text = self.keys.iter().any(|(_, kind)| *kind == IndexKind::Text);
+
+        if self.keys.is_empty() {
+            return err("index must have at least one key");
+        }
+        if self.unique && !plain {
+            return err("unique is only supported for ascending and descending keys");
+        }
+        if self.exp.is_some() && (self.keys.len() != 1 || !plain) {
+            return err("exp is only supported for single field ascending or descending index");
+        }
+        if self
+            .keys
+            .iter()
+            .filter(|(_, kind)| *kind == IndexKind::Hashed)
+            .count()
+            > 1
+        {
+            return err("index can only have one hashed key");
+        }
+        if self
+            .keys
+            .iter()
+            .any(|(_, kind)| *kind == IndexKind::Wildcard)
+            && self.keys.len() > 1
+        {
+            return err("wildcard index can not be compound");
+        }
+        if !text && (!self.weights.is_empty() || self.default_language.is_some()) {
+            return err("weights and default_language are only supported for text index");
+        }
+        if self.extra.sparse && self.extra.partial.is_some() {
+            return err("sparse and partial can not be used together");
+        }
+
+        Ok(())
+    }
+
+    /// Get token representation of the index constant of the model
+    pub fn gen_const(&self, vis: &syn::Visibility, model: &syn::Ident) -> TokenStream {
+        let name = self.name();
+        let ident = syn::Ident::new(&self.const_name, self.span);
+        quote! {
+            #vis const #ident: ModelIndex<#model> = ModelIndex::new(#name);
+        }
+    }
+
+    /// Get token representation
+    pub fn gen(&self) -> TokenStream {
+        let name = self.name();
+        let unique = self.unique;
+        let keys = self.keys.iter().map(|(key, kind)| {
+            let (key, kind) = match kind {
+                IndexKind::Asc => (key.clone(), quote! {IndexKey::Asc}),
+                IndexKind::Desc => (key.clone(), quote! {IndexKey::Desc}),
+                IndexKind::Text => (key.clone(), quote! {IndexKey::Text}),
+                IndexKind::Hashed => (key.clone(), quote! {IndexKey::Hashed}),
+                IndexKind::Sphere2d => (key.clone(), quote! {IndexKey::Sphere2d}),
+                IndexKind::Wildcard => (key.clone(), quote! {IndexKey::Asc}),
+            };
+            quote! { (#key, #kind) }
+        });
+        let exp = match self.exp {
+            Some(exp) => quote! {Some(#exp)},
+            None => quote! {None},
+        };
+        let weights = self
+            .weights
+            .iter()
+            .map(|(key, weight)| quote! { (#key, #weight) });
+        let default_language = match &self.default_language {
+            Some(lang) => quote! {Some(#lang)},
+            None => quote! {None},
+        };
+        let partial = match &self.extra.partial {
+            Some(filter) => quote! {
+                Some({
+                    fn partial() -> ::mangga::bson::Document {
+                        AsFilter::as_filter(#filter)
+                    }
+                    partial
+                })
+            },
+            None => quote! {None},
+        };
+        let sparse = self.extra.sparse;
+        let hidden = self.extra.hidden;
+        let collation = match &self.extra.collation {
+            Some(collation) => {
+                let collation = collation.gen();
+                quote! {Some(#collation)}
+            }
+            None => quote! {None},
+        };
+
+        quote! {
+            IndexSpec {
+                name: #name,
+                keys: &[#(#keys),*],
+                unique: #unique,
+                expire_after: #exp,
+                weights: &[#(#weights),*],
+                default_language: #default_language,
+                partial: #partial,
+                sparse: #sparse,
+                hidden: #hidden,
+                collation: #collation,
+            }
+        }
+    }
+}
+
+/// CollationDef
+///
+/// Represents the collation of an index
+#[derive(Debug, Clone)]
+pub struct CollationDef {
+    pub locale: String,
+    pub strength: Option<u32>,
+    pub case_level: Option<bool>,
+    pub case_first: Option<String>,
+    pub numeric_ordering: Option<bool>,
+    pub alternate: Option<String>,
+    pub max_variable: Option<String>,
+    pub normalization: Option<bool>,
+    pub backwards: Option<bool>,
+}
+
+impl Parse for CollationDef {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let span = input.span();
+        let mut locale = None;
+        let mut def = CollationDef {
+            locale: String::new(),
+            strength: None,
+            case_level: None,
+            case_first: None,
+            numeric_ordering: None,
+            alternate: None,
+            max_variable: None,
+            normalization: None,
+            backwards: None,
+        };
+        let one_of = |lit: syn::LitStr, allowed: &[&str]| {
+            if allowed.contains(&lit.value().as_str()) {
+                Ok(lit.value())
+            } else {
+                Err(syn::Error::new_spanned(
+                    &lit,
+                    format!("allowed values are: {}", allowed.join(", ")),
+                ))
+            }
+        };
+
+        while !input.is_empty() {
+            let id = input.parse::<syn::Ident>()?;
+            let id_str = id.to_string();
+            input.parse::<syn::Token![=]>()?;
+            match &*id_str {
+                "locale" => locale = Some(input.parse::<syn::LitStr>()?.value()),
+                "strength" => {
+                    let lit = input.parse::<syn::LitInt>()?;
+                    let strength = lit.base10_parse()?;
+                    if !(1..=5).contains(&strength) {
+                        return Err(syn::Error::new_spanned(
+                            lit,
+                            "strength must be between 1 and 5",
+                        ));
+                    }
+                    def.strength = Some(strength);
+                }
+                "case_level" => def.case_level = Some(input.parse::<syn::LitBool>()?.value()),
+                "case_first" => {
+                    def.case_first = Some(one_of(input.parse()?, &["upper", "lower", "off"])?)
+                }
+                "numeric_ordering" => {
+                    def.numeric_ordering = Some(input.parse::<syn::LitBool>()?.value())
+                }
+                "alternate" => {
+                    def.alternate = Some(one_of(input.parse()?, &["non-ignorable", "shifted"])?)
+                }
+                "max_variable" => {
+                    def.max_variable = Some(one_of(input.parse()?, &["punct", "space"])?)
+                }
+                "normalization" => def.normalization = Some(input.parse::<syn::LitBool>()?.value()),
+                "backwards" => def.backwards = Some(input.parse::<syn::LitBool>()?.value()),
+                _ => {
+                    return Err(syn::Error::new_spanned(
+                        id,
+                        format!("unknown attribute `{}`", id_str),
+                    ))
+                }
+            }
+
+            if !input.is_empty() {
+                input.parse::<syn::Token![,]>()?;
+            }
+        }
+
+        def.locale = locale.ok_or_else(|| syn::Error::new(span, "locale is required"))?;
+        Ok(def)
+    }
+}
+
+impl CollationDef {
+    /// Get token representation
+    pub fn gen(&self) -> TokenStream {
+        let locale = &self.locale;
+        let opt = |value: Option<TokenStream>| match value {
+            Some(value) => quote! {Some(#value)},
+            None => quote! {None},
+        };
+        let strength = opt(self.strength.map(|v| quote! {#v}));
+        let case_level = opt(self.case_level.map(|v| quote! {#v}));
+        let case_first = opt(self.case_first.as_ref().map(|v| quote! {#v}));
+        let numeric_ordering = opt(self.numeric_ordering.map(|v| quote! {#v}));
+        let alternate = opt(self.alternate.as_ref().map(|v| quote! {#v}));
+        let max_variable = opt(self.max_variable.as_ref().map(|v| quote! {#v}));
+        let normalization = opt(self.normalization.map(|v| quote! {#v}));
+        let backwards = opt(self.backwards.map(|v| quote! {#v}));
+
+        quote! {
+            IndexCollation {
+                strength: #strength,
+                case_level: #case_level,
+                case_first: #case_first,
+                numeric_ordering: #numeric_ordering,
+                alternate: #alternate,
+                max_variable: #max_variable,
+                normalization: #normalization,
+                backwards: #backwards,
+                ..IndexCollation::new(#locale)
+            }
+        }
+    }
+}
+
+/// IndexExtra
+///
+/// Represents the options shared by field and struct indexes
+#[derive(Debug, Clone, Default)]
+pub struct IndexExtra {
+    pub partial: Option<syn::Expr>,
+    pub sparse: bool,
+    pub hidden: bool,
+    pub collation: Option<CollationDef>,
+}
+
+impl IndexExtra {
+    /// Parse the option of the ident, returns false when the ident is not an extra option
+    pub fn parse_option(
+        &mut self,
+        id: &syn::Ident,
+        input: syn::parse::ParseStream,
+    ) -> syn::Result<bool> {
+        match &*id.to_string() {
+            "collation" => {
+                let stream;
+                syn::parenthesized!(stream in input);
+                self.collation = Some(stream.parse()?);
+            }
+            "partial" => {
+                input.parse::<syn::Token![=]>()?;
+                self.partial = Some(input.parse()?);
+            }
+            "sparse" => {
+                input.parse::<syn::Token![=]>()?;
+                self.sparse = input.parse::<syn::LitBool>()?.value();
+            }
+            "hidden" => {
+                input.parse::<syn::Token![=]>()?;
+                self.hidden = input.parse::<syn::LitBool>()?.value();
+            }
+            _ => return Ok(false),
+        }
+
+        Ok(true)
+    }
+}
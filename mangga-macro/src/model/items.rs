@@ -1,11 +1,12 @@
 use super::{
-    attrs::{ItemAttrs, ItemGraphql},
+    attrs::{ItemAttrs, ItemGraphql, ItemIndex},
     fields::ItemFields,
+    index::IndexDef,
 };
 use change_case::{snake_case, upper_case};
 use proc_macro2::TokenStream;
 use quote::{quote, ToTokens};
-use syn::{punctuated::Punctuated, Data, DeriveInput, Ident, Token};
+use syn::{ext::IdentExt, parse::Parse, punctuated::Punctuated, Data, DeriveInput, Ident, Token};
 
 /// Item
 ///
@@ -17,13 +18,15 @@ pub struct Item {
     ident: syn::Ident,
     vis: syn::Visibility,
     fields: ItemFields,
+    indexes: Vec<IndexDef>,
+    collection: Option<TokenStream>,
 }
 
 impl Item {
     /// Parse the derive input
     pub fn parse(input: DeriveInput) -> syn::Result<Self> {
         // since we doesn't support generics, we will check for generics and return an error
-        if input.generics.params.len() > 0 {
+        if !input.generics.params.is_empty() {
             return Err(syn::Error::new_spanned(
                 input.generics,
                 "Generics are not supported",
@@ -73,7 +76,51 @@ impl Item {
         } else {
             syn::parse2::<ItemGraphql>(graphql_attr_tokens.to_token_stream())?
         };
-        let fields = ItemFields::parse(struct_item.fields)?;
+        let fields = ItemFields::parse(struct_item.fields, &input.attrs)?;
+
+        // collect field and struct indexes
+        let mut defs = vec![];
+        for field in &fields.fields {
+            for index in &field.attrs.indexes {
+                defs.push(index.def(&field.name, &field.ident)?);
+            }
+        }
+        for attr in input
+            .attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("index"))
+        {
+            let index = attr.parse_args_with(ItemIndex::parse)?;
+            defs.push(index.def(&fields)?);
+        }
+        let mut names = std::collections::HashSet::new();
+        let mut const_names = std::collections::HashSet::new();
+        for def in &defs {
+            if !names.insert(def.name()) {
+                return Err(syn::Error::new(
+                    def.span,
+                    format!("duplicate index name `{}`", def.name()),
+                ));
+            }
+            if !const_names.insert(def.const_name.clone()) {
+                return Err(syn::Error::new(
+                    def.span,
+                    format!(
+                        "duplicate index constant `{}`, set a different `name` on the index",
+                        def.const_name
+                    ),
+                ));
+            }
+        }
+        let collection = attrs.collection.gen(&fields)?;
+        if attrs.collection.schema.is_some() {
+            if let Some(field) = fields.fields.iter().find(|f| f.serde.flatten) {
+                return Err(syn::Error::new_spanned(
+                    &field.ident,
+                    "flattened fields are not supported with `schema`",
+                ));
+            }
+        }
 
         Ok(Self {
             attrs,
@@ -81,14 +128,15 @@ impl Item {
             ident: input.ident,
             vis: input.vis,
             fields,
+            indexes: defs,
+            collection,
         })
     }
 
     /// Get mod ident
     pub fn mod_ident(&self) -> Ident {
         let ident = &self.ident;
-        let mod_ident = Ident::new(&snake_case(&ident.to_string()), ident.span());
-        mod_ident
+        Ident::new(&snake_case(&ident.to_string()), ident.span())
     }
 }
 
@@ -96,7 +144,14 @@ impl ToTokens for Item {
     fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
         let ident = &self.ident;
         let vis = &self.vis;
-        let ItemAttrs { name, db_name } = &self.attrs;
+        let ItemAttrs {
+            name,
+            db_name,
+            connection,
+            drop_unknown,
+            dry_run,
+            ..
+        } = &self.attrs;
 
         // generate code
         let check_id = self.fields.gen_check_id();
@@ -105,11 +160,45 @@ impl ToTokens for Item {
         let id_field_ident = &self.fields.id_field.ident;
         let graphql_input_ident = Ident::new(&format!("{}Input", ident), ident.span());
         let graphql_res = &self.graphql_attrs.result;
+        let connection = connection.as_ref().map(|connection| {
+            quote! {
+                const CONNECTION: &'static str = #connection;
+            }
+        });
+        let index_policy = (drop_unknown.is_some() || dry_run.is_some()).then(|| {
+            let drop_unknown = drop_unknown.unwrap_or(true);
+            let dry_run = dry_run.unwrap_or(false);
+            quote! {
+                const INDEX_POLICY: IndexPolicy = IndexPolicy {
+                    drop_unknown: #drop_unknown,
+                    dry_run: #dry_run,
+                };
+            }
+        });
 
         // builtin
         let mut builtin_args = Punctuated::<TokenStream, Token![,]>::new();
         let mut builtin_names = Punctuated::<TokenStream, Token![,]>::new();
-        let mut indexes = Punctuated::<TokenStream, Token![,]>::new();
+        let indexes = self.indexes.iter().map(|def| def.gen());
+        let index_consts = self
+            .indexes
+            .iter()
+            .map(|def| def.gen_const(&self.vis, &self.ident));
+        let collection = &self.collection;
+        let json_schema = self.attrs.collection.schema.as_ref().map(|_| {
+            let schema = crate::schema::gen_object(
+                self.fields
+                    .fields
+                    .iter()
+                    .filter(|field| field.is_stored())
+                    .map(|field| (field.name.clone(), &field.ty, field.serde.optional)),
+            );
+            quote! {
+                fn json_schema() -> Option<::mangga::bson::Document> {
+                    Some(#schema)
+                }
+            }
+        });
         let mut graphql_input_fields = Punctuated::<TokenStream, Token![,]>::new();
         let mut graphql_output = quote! {};
         let mut fields = quote! {};
@@ -119,31 +208,30 @@ impl ToTokens for Item {
             let field_ident = &field.ident;
             let field_ty = &field.ty;
             let field_name = &field.name;
-            let const_field_ident =
-                Ident::new(&upper_case(&field_ident.to_string()), field_ident.span());
+            let const_field_ident = Ident::new(
+                &upper_case(&field_ident.unraw().to_string()),
+                field_ident.span(),
+            );
 
-            // generate dsl
-            dsl.extend(quote! {
-                #[derive(Debug, Clone, Copy)]
-                pub struct #field_ident;
-                impl Field for #field_ident {
-                    type Model = #ident;
-                    const NAME: &'static str = #field_name;
-                    type Type = #field_ty;
-                }
-            });
+            // generate dsl, skipped and flattened fields have no key of their own
+            if field.is_stored() {
+                dsl.extend(quote! {
+                    #[derive(Debug, Clone, Copy)]
+                    pub struct #field_ident;
+                    impl Field for #field_ident {
+                        type Model = #ident;
+                        const NAME: &'static str = #field_name;
+                        type Type = #field_ty;
+                    }
+                });
+                fields.extend(quote! {
+                    #vis const #const_field_ident: #mod_ident::#field_ident = #mod_ident::#field_ident;
+                });
+            }
 
             // builtin
             builtin_args.push(quote! { #field_ident: impl Into<#field_ty> });
             builtin_names.push(quote! { #field_ident: #field_ident.into() });
-            fields.extend(quote! {
-                #vis const #const_field_ident: #mod_ident::#field_ident = #mod_ident::#field_ident;
-            });
-
-            for index in &field.attrs.indexes {
-                let token = index.gen(&field.ident);
-                indexes.push(token);
-            }
 
             // graphql
             if field.attrs.graphql.input {
@@ -239,15 +327,20 @@ impl ToTokens for Item {
                     impl Model for #ident {
                         const MODEL_NAME: &'static str = #name;
                         const DB_NAME: &'static str = #db_name;
-                        const INDEXES: &'static [(&'static str, &'static str, i32, bool, Option<u64>)] = &[#indexes];
+                        #connection
+                        const INDEXES: &'static [IndexSpec] = &[#(#indexes),*];
+                        #collection
+                        #index_policy
                         fn id(&self) -> impl Into<ID> {
                             self.#id_field_ident
                         }
+                        #json_schema
                     }
                     impl #ident {
                         #[allow(non_upper_case_globals)]
                         #vis const dsl: #mod_ident::dsl = #mod_ident::dsl;
                         #fields
+                        #(#index_consts)*
                         #vis fn new(#builtin_args) -> Self {
                             Self {
                                 #builtin_names
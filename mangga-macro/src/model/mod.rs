@@ -1,7 +1,9 @@
 use quote::ToTokens;
 
 mod attrs;
+mod collection;
 mod fields;
+mod index;
 mod items;
 
 pub fn parse(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
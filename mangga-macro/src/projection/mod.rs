@@ -0,0 +1,192 @@
+use crate::serde_attrs::{SerdeContainer, SerdeField};
+use change_case::upper_case;
+use proc_macro2::TokenStream;
+use quote::{quote, quote_spanned, ToTokens};
+use syn::{ext::IdentExt, parse::Parse, Data, DeriveInput, Fields, Ident};
+
+/// ProjectionAttrs
+///
+/// Represents the attributes of a projection struct
+#[derive(Debug, Clone)]
+struct ProjectionAttrs {
+    model: syn::Type,
+}
+
+impl Parse for ProjectionAttrs {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let span = input.span();
+        let mut model = None;
+
+        while !input.is_empty() {
+            let id = input.parse::<syn::Ident>()?;
+            input.parse::<syn::Token![=]>()?;
+            let id_str = id.to_string();
+            match &*id_str {
+                "model" => model = Some(input.parse::<syn::Type>()?),
+                _ => {
+                    return Err(syn::Error::new_spanned(
+                        id,
+                        format!("unknown attribute `{}`", id_str),
+                    ))
+                }
+            }
+
+            if !input.is_empty() {
+                input.parse::<syn::Token![,]>()?;
+            }
+        }
+
+        let model = model.ok_or_else(|| syn::Error::new(span, "model attribute is required"))?;
+
+        Ok(ProjectionAttrs { model })
+    }
+}
+
+/// ProjectionField
+///
+/// Represents a field of a projection struct
+#[derive(Debug)]
+struct ProjectionField {
+    ident: Ident,
+    ty: syn::Type,
+    /// Key the field is deserialized from
+    key: String,
+}
+
+impl ProjectionField {
+    /// Parse the field, its key is computed from the serde attributes like the model fields
+    fn parse(field: syn::Field, container: &SerdeContainer) -> syn::Result<Option<Self>> {
+        let Some(ident) = field.ident else {
+            return Ok(None);
+        };
+        let serde = SerdeField::parse_field(&ident, &field.attrs, container)?;
+        if serde.flatten {
+            return Err(syn::Error::new_spanned(
+                ident,
+                "flattened fields are not supported",
+            ));
+        }
+        if serde.skip {
+            return Ok(None);
+        }
+
+        Ok(Some(Self {
+            ident,
+            ty: field.ty,
+            key: serde.name,
+        }))
+    }
+}
+
+/// Projection
+///
+/// Represents a struct of partial model
+#[derive(Debug)]
+struct Projection {
+    attrs: ProjectionAttrs,
+    ident: Ident,
+    fields: Vec<ProjectionField>,
+}
+
+impl Projection {
+    /// Parse the derive input
+    fn parse(input: DeriveInput) -> syn::Result<Self> {
+        if !input.generics.params.is_empty() {
+            return Err(syn::Error::new_spanned(
+                input.generics,
+                "Generics are not supported",
+            ));
+        }
+
+        let attr = input
+            .attrs
+            .iter()
+            .find(|attr| attr.path().is_ident("projection"))
+            .ok_or_else(|| {
+                syn::Error::new_spanned(&input.ident, "`#[projection(model = ...)]` is required")
+            })?;
+        let attrs = attr.parse_args::<ProjectionAttrs>()?;
+
+        let named_fields = match input.data {
+            Data::Struct(dt) => match dt.fields {
+                Fields::Named(fields) => fields.named,
+                fields => {
+                    return Err(syn::Error::new_spanned(
+                        fields,
+                        "Only named fields are supported",
+                    ))
+                }
+            },
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    input.ident,
+                    "Only structs are supported",
+                ))
+            }
+        };
+
+        let container = SerdeContainer::parse(&input.attrs)?;
+        let fields = named_fields
+            .into_iter()
+            .filter_map(|field| ProjectionField::parse(field, &container).transpose())
+            .collect::<syn::Result<_>>()?;
+
+        Ok(Self {
+            attrs,
+            ident: input.ident,
+            fields,
+        })
+    }
+}
+
+impl ToTokens for Projection {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let ident = &self.ident;
+        let model = &self.attrs.model;
+        let mut names = quote! {};
+
+        for field in &self.fields {
+            let field_ident = &field.ident;
+            let field_ty = &field.ty;
+            let key = &field.key;
+            let const_field_ident = Ident::new(
+                &upper_case(&field_ident.unraw().to_string()),
+                field_ident.span(),
+            );
+            // the key is checked at compile time, the error points at the field
+            names.extend(quote_spanned! {field_ident.span()=>
+                project_field::<#model, _, #field_ty>(&#model::#const_field_ident, #key),
+            });
+        }
+
+        tokens.extend(quote! {
+            const _: () = {
+                #[allow(unused_imports)]
+                use ::mangga::prelude::*;
+                const NAMES: &[&str] = &[#names];
+                impl Projection for #ident {
+                    type Model = #model;
+                    fn projection() -> ::mangga::bson::Document {
+                        let mut doc = ::mangga::bson::Document::new();
+                        for name in NAMES {
+                            doc.insert(*name, 1);
+                        }
+                        if !NAMES.contains(&"_id") {
+                            doc.insert("_id", 0);
+                        }
+                        doc
+                    }
+                }
+            };
+        });
+    }
+}
+
+pub fn parse(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = syn::parse_macro_input!(input as syn::DeriveInput);
+
+    match Projection::parse(input) {
+        Ok(item) => item.to_token_stream().into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
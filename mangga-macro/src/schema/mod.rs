@@ -0,0 +1,115 @@
+use crate::serde_attrs::{SerdeContainer, SerdeField};
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Data, DeriveInput, Fields};
+
+/// Get token representation of the schema of an object with the given field keys and types
+///
+/// Optional fields may be missing from the document whatever their type
+pub fn gen_object<'a>(fields: impl Iterator<Item = (String, &'a syn::Type, bool)>) -> TokenStream {
+    let fields = fields.map(|(name, ty, optional)| {
+        let required = match optional {
+            true => quote! { false },
+            false => quote! { <#ty as ::mangga::prelude::BsonSchema>::required() },
+        };
+        quote! {
+            (
+                #name,
+                <#ty as ::mangga::prelude::BsonSchema>::bson_schema(),
+                #required,
+            )
+        }
+    });
+    quote! {
+        ::mangga::prelude::object_schema(vec![#(#fields),*])
+    }
+}
+
+/// Get token representation of the schema of the derive input
+fn gen(input: DeriveInput) -> syn::Result<TokenStream> {
+    if !input.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(
+            input.generics,
+            "Generics are not supported",
+        ));
+    }
+
+    let container = SerdeContainer::parse(&input.attrs)?;
+    let schema = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => {
+                let mut keys = vec![];
+                for field in &fields.named {
+                    let ident = field.ident.as_ref().expect("named field");
+                    let serde = SerdeField::parse_field(ident, &field.attrs, &container)?;
+                    if serde.flatten {
+                        return Err(syn::Error::new_spanned(
+                            ident,
+                            "flattened fields are not supported",
+                        ));
+                    }
+                    if !serde.skip {
+                        keys.push((serde.name, &field.ty, serde.optional));
+                    }
+                }
+                gen_object(keys.into_iter())
+            }
+            Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+                let ty = &fields.unnamed[0].ty;
+                quote! { <#ty as ::mangga::prelude::BsonSchema>::bson_schema() }
+            }
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    &input.ident,
+                    "Only structs with named fields or a single unnamed field are supported",
+                ))
+            }
+        },
+        Data::Enum(data) => {
+            if container.tagged {
+                return Err(syn::Error::new_spanned(
+                    &input.ident,
+                    "Tagged enums are not supported",
+                ));
+            }
+            let mut variants = vec![];
+            for variant in &data.variants {
+                if !matches!(variant.fields, Fields::Unit) {
+                    return Err(syn::Error::new_spanned(
+                        variant,
+                        "Only unit enum variants are supported",
+                    ));
+                }
+                let serde = SerdeField::parse_variant(&variant.ident, &variant.attrs, &container)?;
+                if !serde.skip {
+                    variants.push(serde.name);
+                }
+            }
+            quote! { ::mangga::bson::doc! { "enum": [#(#variants),*] } }
+        }
+        Data::Union(_) => {
+            return Err(syn::Error::new_spanned(
+                &input.ident,
+                "Unions are not supported",
+            ))
+        }
+    };
+
+    let ident = &input.ident;
+    Ok(quote! {
+        impl ::mangga::prelude::BsonSchema for #ident {
+            fn bson_schema() -> ::mangga::bson::Document {
+                #schema
+            }
+        }
+    })
+}
+
+pub fn parse(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = syn::parse_macro_input!(input as DeriveInput);
+
+    match gen(input) {
+        Ok(tokens) => tokens.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
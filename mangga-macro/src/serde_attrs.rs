@@ -0,0 +1,318 @@
+use syn::{ext::IdentExt, meta::ParseNestedMeta, Attribute};
+
+/// RenameRule
+///
+/// Represents the case conversion of `#[serde(rename_all = "...")]`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RenameRule {
+    Lower,
+    Upper,
+    Pascal,
+    Camel,
+    Snake,
+    ScreamingSnake,
+    Kebab,
+    ScreamingKebab,
+}
+
+impl RenameRule {
+    /// Get the rule from its serde name
+    fn from_name(lit: &syn::LitStr) -> syn::Result<Self> {
+        match &*lit.value() {
+            "lowercase" => Ok(RenameRule::Lower),
+            "UPPERCASE" => Ok(RenameRule::Upper),
+            "PascalCase" => Ok(RenameRule::Pascal),
+            "camelCase" => Ok(RenameRule::Camel),
+            "snake_case" => Ok(RenameRule::Snake),
+            "SCREAMING_SNAKE_CASE" => Ok(RenameRule::ScreamingSnake),
+            "kebab-case" => Ok(RenameRule::Kebab),
+            "SCREAMING-KEBAB-CASE" => Ok(RenameRule::ScreamingKebab),
+            _ => Err(syn::Error::new_spanned(lit, "unknown rename rule")),
+        }
+    }
+
+    /// Apply the rule to a snake_case field name, the way serde does
+    pub fn apply_to_field(&self, field: &str) -> String {
+        match self {
+            RenameRule::Lower | RenameRule::Snake => field.to_string(),
+            RenameRule::Upper | RenameRule::ScreamingSnake => field.to_ascii_uppercase(),
+            RenameRule::Pascal => {
+                let mut pascal = String::new();
+                let mut capitalize = true;
+                for ch in field.chars() {
+                    if ch == '_' {
+                        capitalize = true;
+                    } else if capitalize {
+                        pascal.push(ch.to_ascii_uppercase());
+                        capitalize = false;
+                    } else {
+                        pascal.push(ch);
+                    }
+                }
+                pascal
+            }
+            RenameRule::Camel => lower_first(&RenameRule::Pascal.apply_to_field(field)),
+            RenameRule::Kebab => field.replace('_', "-"),
+            RenameRule::ScreamingKebab => field.to_ascii_uppercase().replace('_', "-"),
+        }
+    }
+
+    /// Apply the rule to a PascalCase variant name, the way serde does
+    pub fn apply_to_variant(&self, variant: &str) -> String {
+        match self {
+            RenameRule::Pascal => variant.to_string(),
+            RenameRule::Lower => variant.to_ascii_lowercase(),
+            RenameRule::Upper => variant.to_ascii_uppercase(),
+            RenameRule::Camel => lower_first(variant),
+            RenameRule::Snake => {
+                let mut snake = String::new();
+                for (i, ch) in variant.char_indices() {
+                    if i > 0 && ch.is_uppercase() {
+                        snake.push('_');
+                    }
+                    snake.push(ch.to_ascii_lowercase());
+                }
+                snake
+            }
+            RenameRule::ScreamingSnake => RenameRule::Snake
+                .apply_to_variant(variant)
+                .to_ascii_uppercase(),
+            RenameRule::Kebab => RenameRule::Snake
+                .apply_to_variant(variant)
+                .replace('_', "-"),
+            RenameRule::ScreamingKebab => RenameRule::ScreamingSnake
+                .apply_to_variant(variant)
+                .replace('_', "-"),
+        }
+    }
+}
+
+/// Lowercase the first character, a field made of underscores has none
+fn lower_first(name: &str) -> String {
+    let mut chars = name.chars();
+    match chars.next() {
+        Some(first) => first.to_ascii_lowercase().to_string() + chars.as_str(),
+        None => String::new(),
+    }
+}
+
+/// Skip the value of a serde option we don't need
+fn skip_value(meta: &ParseNestedMeta) -> syn::Result<()> {
+    if meta.input.peek(syn::Token![=]) {
+        meta.value()?.parse::<syn::Expr>()?;
+    } else if meta.input.peek(syn::token::Paren) {
+        meta.parse_nested_meta(|nested| skip_value(&nested))?;
+    }
+    Ok(())
+}
+
+/// Parse `name = "..."` or `name(serialize = "...", deserialize = "...")`, rejecting different
+/// names
+fn parse_names(meta: &ParseNestedMeta) -> syn::Result<syn::LitStr> {
+    if meta.input.peek(syn::Token![=]) {
+        return meta.value()?.parse();
+    }
+
+    let mut serialize = None;
+    let mut deserialize = None;
+    meta.parse_nested_meta(|nested| {
+        if nested.path.is_ident("serialize") {
+            serialize = Some(nested.value()?.parse::<syn::LitStr>()?);
+        } else if nested.path.is_ident("deserialize") {
+            deserialize = Some(nested.value()?.parse::<syn::LitStr>()?);
+        } else {
+            return Err(nested.error("expected `serialize` or `deserialize`"));
+        }
+        Ok(())
+    })?;
+    match (serialize, deserialize) {
+        (Some(ser), Some(de)) if ser.value() == de.value() => Ok(ser),
+        _ => Err(meta.error("different serialize and deserialize names are not supported")),
+    }
+}
+
+/// SerdeContainer
+///
+/// Represents the serde attributes of a struct or an enum
+#[derive(Debug, Clone, Default)]
+pub struct SerdeContainer {
+    pub rename_all: Option<RenameRule>,
+    pub tagged: bool,
+}
+
+impl SerdeContainer {
+    /// Parse the serde attributes of a container
+    pub fn parse(attrs: &[Attribute]) -> syn::Result<Self> {
+        let mut container = SerdeContainer::default();
+        for attr in attrs.iter().filter(|attr| attr.path().is_ident("serde")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("rename_all") {
+                    container.rename_all = Some(RenameRule::from_name(&parse_names(&meta)?)?);
+                } else if meta.path.is_ident("tag") || meta.path.is_ident("untagged") {
+                    container.tagged = true;
+                    skip_value(&meta)?;
+                } else if meta.path.is_ident("transparent")
+                    || meta.path.is_ident("into")
+                    || meta.path.is_ident("from")
+                {
+                    return Err(meta.error("unsupported serde attribute"));
+                } else {
+                    skip_value(&meta)?;
+                }
+                Ok(())
+            })?;
+        }
+        Ok(container)
+    }
+}
+
+/// SerdeField
+///
+/// Represents the serde attributes of a field or a variant
+#[derive(Debug, Clone)]
+pub struct SerdeField {
+    /// Key of the field in the document
+    pub name: String,
+    /// Field is never stored
+    pub skip: bool,
+    /// Fields of the field are stored in the parent document
+    pub flatten: bool,
+    /// Field may be missing from the document
+    pub optional: bool,
+}
+
+impl SerdeField {
+    /// Parse the serde attributes of a field
+    pub fn parse_field(
+        ident: &syn::Ident,
+        attrs: &[Attribute],
+        container: &SerdeContainer,
+    ) -> syn::Result<Self> {
+        let name = ident.unraw().to_string();
+        let name = match container.rename_all {
+            Some(rule) => rule.apply_to_field(&name),
+            None => name,
+        };
+        Self::parse(ident, name, attrs)
+    }
+
+    /// Parse the serde attributes of a variant
+    pub fn parse_variant(
+        ident: &syn::Ident,
+        attrs: &[Attribute],
+        container: &SerdeContainer,
+    ) -> syn::Result<Self> {
+        let name = ident.unraw().to_string();
+        let name = match container.rename_all {
+            Some(rule) => rule.apply_to_variant(&name),
+            None => name,
+        };
+        Self::parse(ident, name, attrs)
+    }
+
+    fn parse(ident: &syn::Ident, name: String, attrs: &[Attribute]) -> syn::Result<Self> {
+        let mut field = SerdeField {
+            name,
+            skip: false,
+            flatten: false,
+            optional: false,
+        };
+        let mut renamed = false;
+        for attr in attrs.iter().filter(|attr| attr.path().is_ident("serde")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("rename") {
+                    field.name = parse_names(&meta)?.value();
+                    renamed = true;
+                } else if meta.path.is_ident("skip") || meta.path.is_ident("skip_serializing") {
+                    field.skip = true;
+                } else if meta.path.is_ident("flatten") {
+                    field.flatten = true;
+                } else if meta.path.is_ident("skip_serializing_if") {
+                    field.optional = true;
+                    skip_value(&meta)?;
+                } else {
+                    skip_value(&meta)?;
+                }
+                Ok(())
+            })?;
+        }
+
+        if field.name.is_empty() && !field.skip && !field.flatten {
+            return Err(syn::Error::new_spanned(
+                ident,
+                "the key of the field is empty, use `#[serde(rename = \"...\")]`",
+            ));
+        }
+        if field.flatten && (renamed || field.skip) {
+            return Err(syn::Error::new_spanned(
+                ident,
+                "`flatten` cannot be combined with `rename` or `skip`",
+            ));
+        }
+        Ok(field)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use syn::parse_quote;
+
+    const RULES: [RenameRule; 8] = [
+        RenameRule::Lower,
+        RenameRule::Upper,
+        RenameRule::Pascal,
+        RenameRule::Camel,
+        RenameRule::Snake,
+        RenameRule::ScreamingSnake,
+        RenameRule::Kebab,
+        RenameRule::ScreamingKebab,
+    ];
+
+    #[test]
+    fn rename_rules_of_fields() {
+        let names = RULES.map(|rule| rule.apply_to_field("user_id"));
+        assert_eq!(
+            names,
+            [
+                "user_id", "USER_ID", "UserId", "userId", "user_id", "USER_ID", "user-id",
+                "USER-ID"
+            ]
+        );
+        assert_eq!(RenameRule::Camel.apply_to_field("id"), "id");
+    }
+
+    #[test]
+    fn rename_rules_of_variants() {
+        let names = RULES.map(|rule| rule.apply_to_variant("UserId"));
+        assert_eq!(
+            names,
+            ["userid", "USERID", "UserId", "userId", "user_id", "USER_ID", "user-id", "USER-ID"]
+        );
+        assert_eq!(RenameRule::Snake.apply_to_variant("A"), "a");
+    }
+
+    #[test]
+    fn rename_rules_of_underscore_fields() {
+        assert_eq!(RenameRule::Pascal.apply_to_field("__"), "");
+        assert_eq!(RenameRule::Camel.apply_to_field("__"), "");
+        assert_eq!(RenameRule::Kebab.apply_to_field("__"), "--");
+    }
+
+    #[test]
+    fn empty_key_is_an_error_unless_renamed() {
+        let container = SerdeContainer {
+            rename_all: Some(RenameRule::Camel),
+            tagged: false,
+        };
+        let ident: syn::Ident = parse_quote!(__);
+        assert!(SerdeField::parse_field(&ident, &[], &container).is_err());
+
+        let attrs: Vec<Attribute> = vec![parse_quote!(#[serde(rename = "underscores")])];
+        let field = SerdeField::parse_field(&ident, &attrs, &container).unwrap();
+        assert_eq!(field.name, "underscores");
+
+        let attrs: Vec<Attribute> = vec![parse_quote!(#[serde(skip)])];
+        assert!(SerdeField::parse_field(&ident, &attrs, &container).is_ok());
+    }
+}
@@ -0,0 +1,393 @@
+use crate::{db::Mangga, Error, Result};
+use mongodb::options::{
+    Acknowledgment,
+    AuthMechanism,
+    ClientOptions,
+    Credential,
+    ReadConcern,
+    ReadPreference,
+    SelectionCriteria,
+    Tls,
+    TlsOptions,
+    WriteConcern,
+};
+use std::{
+    path::{Path, PathBuf},
+    str::FromStr,
+    time::Duration,
+};
+
+/// Default prefix of the environment variables
+const ENV_PREFIX: &str = "MANGGA_";
+
+/// ConnectConfig
+///
+/// Represents the configuration of the connection
+///
+/// Values set on the builder override the options of the URI
+#[derive(Clone, Default)]
+pub struct ConnectConfig {
+    uri: String,
+    databases: Vec<String>,
+    app_name: Option<String>,
+    min_pool_size: Option<u32>,
+    max_pool_size: Option<u32>,
+    max_idle_time: Option<Duration>,
+    connect_timeout: Option<Duration>,
+    server_selection_timeout: Option<Duration>,
+    compressors: Option<Vec<String>>,
+    read_preference: Option<ReadPreference>,
+    read_concern: Option<ReadConcern>,
+    write_concern: Option<WriteConcern>,
+    tls_ca_file: Option<PathBuf>,
+    tls_cert_key_file: Option<PathBuf>,
+    tls_allow_invalid_certificates: Option<bool>,
+    username: Option<String>,
+    password: Option<String>,
+    auth_source: Option<String>,
+    auth_mechanism: Option<AuthMechanism>,
+}
+
+impl ConnectConfig {
+    /// Create a new config of the URI
+    pub fn new<U: Into<String>>(uri: U) -> Self {
+        Self {
+            uri: uri.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Load the config from the environment variables prefixed with `MANGGA_`
+    ///
+    /// See [`ConnectConfig::from_env_prefixed`] for the list of variables
+    pub fn from_env() -> Result<Self> {
+        Self::from_env_prefixed(ENV_PREFIX)
+    }
+
+    /// Load the config from the environment variables with the prefix
+    ///
+    /// `URI` is required, the others are optional:
+    /// `DATABASES` (comma separated), `APP_NAME`, `MIN_POOL_SIZE`, `MAX_POOL_SIZE`,
+    /// `MAX_IDLE_TIME_MS`, `CONNECT_TIMEOUT_MS`, `SERVER_SELECTION_TIMEOUT_MS`,
+    /// `COMPRESSORS` (comma separated), `READ_PREFERENCE`, `READ_CONCERN`, `WRITE_CONCERN`,
+    /// `TLS_CA_FILE`, `TLS_CERT_KEY_FILE`, `TLS_ALLOW_INVALID_CERTIFICATES`, `USERNAME`,
+    /// `PASSWORD`, `PASSWORD_FILE`, `AUTH_SOURCE` and `AUTH_MECHANISM`
+    pub fn from_env_prefixed<P: AsRef<str>>(prefix: P) -> Result<Self> {
+        let env = |name: &str| {
+            let key = format!("{}{}", prefix.as_ref(), name);
+            std::env::var(&key)
+                .ok()
+                .filter(|v| !v.is_empty())
+                .map(|v| (key, v))
+        };
+        let list = |value: String| {
+            value
+                .split(',')
+                .map(|v| v.trim().to_string())
+                .filter(|v| !v.is_empty())
+                .collect::<Vec<_>>()
+        };
+
+        let (_, uri) = env("URI").ok_or_else(|| {
+            Error::Init(format!(
+                "environment variable `{}URI` is not set",
+                prefix.as_ref()
+            ))
+        })?;
+        let mut config = Self::new(uri);
+
+        if let Some((_, v)) = env("DATABASES") {
+            config.databases = list(v);
+        }
+        config.app_name = env("APP_NAME").map(|(_, v)| v);
+        config.min_pool_size = env("MIN_POOL_SIZE").map(parse).transpose()?;
+        config.max_pool_size = env("MAX_POOL_SIZE").map(parse).transpose()?;
+        config.max_idle_time = env("MAX_IDLE_TIME_MS").map(parse_millis).transpose()?;
+        config.connect_timeout = env("CONNECT_TIMEOUT_MS").map(parse_millis).transpose()?;
+        config.server_selection_timeout = env("SERVER_SELECTION_TIMEOUT_MS")
+            .map(parse_millis)
+            .transpose()?;
+        config.compressors = env("COMPRESSORS").map(|(_, v)| list(v));
+        config.read_preference = env("READ_PREFERENCE")
+            .map(parse_read_preference)
+            .transpose()?;
+        config.read_concern = env("READ_CONCERN").map(|(_, v)| ReadConcern::custom(v));
+        config.write_concern = env("WRITE_CONCERN").map(|(_, v)| {
+            let mut write_concern = WriteConcern::default();
+            write_concern.w = Some(match v.parse::<u32>() {
+                Ok(n) => Acknowledgment::from(n),
+                Err(_) => Acknowledgment::from(v),
+            });
+            write_concern
+        });
+        config.tls_ca_file = env("TLS_CA_FILE").map(|(_, v)| v.into());
+        config.tls_cert_key_file = env("TLS_CERT_KEY_FILE").map(|(_, v)| v.into());
+        config.tls_allow_invalid_certificates = env("TLS_ALLOW_INVALID_CERTIFICATES")
+            .map(parse)
+            .transpose()?;
+        config.username = env("USERNAME").map(|(_, v)| v);
+        config.password = env("PASSWORD").map(|(_, v)| v);
+        if let Some((_, path)) = env("PASSWORD_FILE") {
+            config = config.password_file(path)?;
+        }
+        config.auth_source = env("AUTH_SOURCE").map(|(_, v)| v);
+        config.auth_mechanism = env("AUTH_MECHANISM")
+            .map(|(_, v)| AuthMechanism::from_str(&v))
+            .transpose()?;
+
+        Ok(config)
+    }
+
+    /// Add database name
+    pub fn database<N: Into<String>>(mut self, name: N) -> Self {
+        self.databases.push(name.into());
+        self
+    }
+
+    /// Add database names
+    pub fn databases<D: IntoIterator<Item = S>, S: Into<String>>(mut self, names: D) -> Self {
+        self.databases.extend(names.into_iter().map(Into::into));
+        self
+    }
+
+    /// Set the application name sent to the server
+    pub fn app_name<N: Into<String>>(mut self, name: N) -> Self {
+        self.app_name = Some(name.into());
+        self
+    }
+
+    /// Set the minimum number of connections in the pool
+    pub fn min_pool_size(mut self, size: u32) -> Self {
+        self.min_pool_size = Some(size);
+        self
+    }
+
+    /// Set the maximum number of connections in the pool
+    pub fn max_pool_size(mut self, size: u32) -> Self {
+        self.max_pool_size = Some(size);
+        self
+    }
+
+    /// Set the maximum time a connection may stay idle in the pool
+    pub fn max_idle_time(mut self, time: Duration) -> Self {
+        self.max_idle_time = Some(time);
+        self
+    }
+
+    /// Set the connect timeout
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Set the server selection timeout
+    pub fn server_selection_timeout(mut self, timeout: Duration) -> Self {
+        self.server_selection_timeout = Some(timeout);
+        self
+    }
+
+    /// Set the compressors by name in the order of preference
+    ///
+    /// Each compressor requires the corresponding compression feature of `mongodb`
+    pub fn compressors<C: IntoIterator<Item = S>, S: Into<String>>(mut self, names: C) -> Self {
+        self.compressors = Some(names.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Set the default read preference
+    pub fn read_preference(mut self, read_preference: ReadPreference) -> Self {
+        self.read_preference = Some(read_preference);
+        self
+    }
+
+    /// Set the default read concern
+    pub fn read_concern(mut self, read_concern: ReadConcern) -> Self {
+        self.read_concern = Some(read_concern);
+        self
+    }
+
+    /// Set the default write concern
+    pub fn write_concern(mut self, write_concern: WriteConcern) -> Self {
+        self.write_concern = Some(write_concern);
+        self
+    }
+
+    /// Enable TLS with the CA file
+    pub fn tls_ca_file<P: Into<PathBuf>>(mut self, path: P) -> Self {
+        self.tls_ca_file = Some(path.into());
+        self
+    }
+
+    /// Enable TLS with the client certificate and key file
+    pub fn tls_cert_key_file<P: Into<PathBuf>>(mut self, path: P) -> Self {
+        self.tls_cert_key_file = Some(path.into());
+        self
+    }
+
+    /// Allow invalid server certificates
+    pub fn tls_allow_invalid_certificates(mut self, allow: bool) -> Self {
+        self.tls_allow_invalid_certificates = Some(allow);
+        self
+    }
+
+    /// Set the username and password
+    pub fn credentials<U: Into<String>, P: Into<String>>(
+        mut self,
+        username: U,
+        password: P,
+    ) -> Self {
+        self.username = Some(username.into());
+        self.password = Some(password.into());
+        self
+    }
+
+    /// Read the password from the file, trailing whitespace is trimmed
+    pub fn password_file<P: AsRef<Path>>(mut self, path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let password = std::fs::read_to_string(path)
+            .map_err(|e| Error::Init(format!("failed to read {}: {}", path.display(), e)))?;
+        self.password = Some(password.trim_end().to_string());
+        Ok(self)
+    }
+
+    /// Set the database used to authenticate
+    pub fn auth_source<S: Into<String>>(mut self, source: S) -> Self {
+        self.auth_source = Some(source.into());
+        self
+    }
+
+    /// Set the authentication mechanism
+    pub fn auth_mechanism(mut self, mechanism: AuthMechanism) -> Self {
+        self.auth_mechanism = Some(mechanism);
+        self
+    }
+
+    /// Build the client options
+    pub async fn client_options(&self) -> Result<ClientOptions> {
+        // compressors are only available with the compression features of the driver,
+        // so they are passed through the URI to be validated by the driver
+        let uri = match &self.compressors {
+            Some(compressors) => with_query(&self.uri, "compressors", &compressors.join(",")),
+            None => self.uri.clone(),
+        };
+        let mut opts = ClientOptions::parse(&uri).await?;
+
+        if let Some(app_name) = &self.app_name {
+            opts.app_name = Some(app_name.clone());
+        }
+        if let Some(size) = self.min_pool_size {
+            opts.min_pool_size = Some(size);
+        }
+        if let Some(size) = self.max_pool_size {
+            opts.max_pool_size = Some(size);
+        }
+        if let Some(time) = self.max_idle_time {
+            opts.max_idle_time = Some(time);
+        }
+        if let Some(timeout) = self.connect_timeout {
+            opts.connect_timeout = Some(timeout);
+        }
+        if let Some(timeout) = self.server_selection_timeout {
+            opts.server_selection_timeout = Some(timeout);
+        }
+        if let Some(read_preference) = &self.read_preference {
+            opts.selection_criteria =
+                Some(SelectionCriteria::ReadPreference(read_preference.clone()));
+        }
+        if let Some(read_concern) = &self.read_concern {
+            opts.read_concern = Some(read_concern.clone());
+        }
+        if let Some(write_concern) = &self.write_concern {
+            opts.write_concern = Some(write_concern.clone());
+        }
+
+        if self.tls_ca_file.is_some()
+            || self.tls_cert_key_file.is_some()
+            || self.tls_allow_invalid_certificates.is_some()
+        {
+            let mut tls = match opts.tls.take() {
+                Some(Tls::Enabled(tls)) => tls,
+                _ => TlsOptions::default(),
+            };
+            if let Some(path) = &self.tls_ca_file {
+                tls.ca_file_path = Some(path.clone());
+            }
+            if let Some(path) = &self.tls_cert_key_file {
+                tls.cert_key_file_path = Some(path.clone());
+            }
+            if let Some(allow) = self.tls_allow_invalid_certificates {
+                tls.allow_invalid_certificates = Some(allow);
+            }
+            opts.tls = Some(Tls::Enabled(tls));
+        }
+
+        if self.username.is_some()
+            || self.password.is_some()
+            || self.auth_source.is_some()
+            || self.auth_mechanism.is_some()
+        {
+            let credential = opts.credential.get_or_insert_with(Credential::default);
+            if let Some(username) = &self.username {
+                credential.username = Some(username.clone());
+            }
+            if let Some(password) = &self.password {
+                credential.password = Some(password.clone());
+            }
+            if let Some(source) = &self.auth_source {
+                credential.source = Some(source.clone());
+            }
+            if let Some(mechanism) = &self.auth_mechanism {
+                credential.mechanism = Some(mechanism.clone());
+            }
+        }
+
+        Ok(opts)
+    }
+
+    /// Connect to the cluster
+    pub async fn connect(&self) -> Result<Mangga> {
+        let client = mongodb::Client::with_options(self.client_options().await?)?;
+        Ok(Mangga::new(client, &self.databases))
+    }
+}
+
+/// Append the query option to the URI
+fn with_query(uri: &str, key: &str, value: &str) -> String {
+    if uri.contains('?') {
+        format!("{}&{}={}", uri, key, value)
+    } else {
+        let hosts = uri.split_once("://").map(|(_, rest)| rest).unwrap_or(uri);
+        let sep = if hosts.contains('/') { "?" } else { "/?" };
+        format!("{}{}{}={}", uri, sep, key, value)
+    }
+}
+
+/// Parse the value of the environment variable
+fn parse<T: FromStr>((key, value): (String, String)) -> Result<T>
+where
+    T::Err: std::fmt::Display,
+{
+    value
+        .parse()
+        .map_err(|e| Error::Init(format!("invalid value of `{}`: {}", key, e)))
+}
+
+/// Parse the milliseconds of the environment variable
+fn parse_millis(var: (String, String)) -> Result<Duration> {
+    parse::<u64>(var).map(Duration::from_millis)
+}
+
+/// Parse the read preference mode of the environment variable
+fn parse_read_preference((key, value): (String, String)) -> Result<ReadPreference> {
+    match value.as_str() {
+        "primary" => Ok(ReadPreference::Primary),
+        "primaryPreferred" => Ok(ReadPreference::PrimaryPreferred { options: None }),
+        "secondary" => Ok(ReadPreference::Secondary { options: None }),
+        "secondaryPreferred" => Ok(ReadPreference::SecondaryPreferred { options: None }),
+        "nearest" => Ok(ReadPreference::Nearest { options: None }),
+        _ => Err(Error::Init(format!(
+            "invalid value of `{}`: {}",
+            key, value
+        ))),
+    }
+}
@@ -1,22 +1,173 @@
-use crate::{Error, Result};
-use once_cell::sync::OnceCell;
-use std::collections::HashMap;
+use crate::{config::ConnectConfig, traits::Model, Error, Result, Transaction};
+use bson::doc;
+use futures::lock::Mutex;
+use once_cell::sync::Lazy;
+use std::{
+    collections::HashMap,
+    future::Future,
+    sync::{Arc, RwLock},
+};
 
-/// Client
+/// Name of the default connection
+pub const DEFAULT_CONNECTION: &str = "default";
+
+/// Whether the model is set up in the tenant database
+type SetupState = Arc<Mutex<bool>>;
+
+/// Mangga
 ///
-/// Represents a MongoDB client
-struct Client {
-    #[allow(dead_code)]
-    c: mongodb::Client,
-    db: HashMap<String, mongodb::Database>,
+/// Represents a connection to a MongoDB cluster with its databases
+///
+/// The handle is cheap to clone and can be registered by name to be used by the models
+#[derive(Debug, Clone)]
+pub struct Mangga {
+    client: mongodb::Client,
+    db: Arc<RwLock<HashMap<String, mongodb::Database>>>,
+    setup: Arc<RwLock<HashMap<(String, &'static str), SetupState>>>,
 }
 
-/// MANGGA stores the database connection
-static MANGGA: OnceCell<Client> = OnceCell::new();
+impl Mangga {
+    /// Create a new connection from the client
+    pub fn new<D: IntoIterator<Item = S>, S: AsRef<str>>(
+        client: mongodb::Client,
+        databases: D,
+    ) -> Self {
+        let db = databases
+            .into_iter()
+            .map(|name| (name.as_ref().to_string(), client.database(name.as_ref())))
+            .collect();
+
+        Self {
+            client,
+            db: Arc::new(RwLock::new(db)),
+            setup: Default::default(),
+        }
+    }
+
+    /// Connect to the cluster
+    pub async fn connect<U: AsRef<str>, D: IntoIterator<Item = S>, S: AsRef<str>>(
+        uri: U,
+        databases: D,
+    ) -> Result<Self> {
+        let client = mongodb::Client::with_uri_str(uri.as_ref()).await?;
+        Ok(Self::new(client, databases))
+    }
+
+    /// Get the client
+    pub fn client(&self) -> &mongodb::Client {
+        &self.client
+    }
+
+    /// Get the database
+    pub fn database<T: AsRef<str>>(&self, name: T) -> Result<mongodb::Database> {
+        self.db
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(name.as_ref())
+            .cloned()
+            .ok_or_else(|| Error::Init(format!("Database `{}` is not registered", name.as_ref())))
+    }
+
+    /// Get the database, registering it when it is not registered yet
+    pub fn database_or_register<T: AsRef<str>>(&self, name: T) -> mongodb::Database {
+        self.db
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .entry(name.as_ref().to_string())
+            .or_insert_with(|| self.client.database(name.as_ref()))
+            .clone()
+    }
+
+    /// Get the database of the tenant for the model
+    ///
+    /// The database is registered when unseen and the model is set up in it once
+    pub async fn tenant<M: Model, T: AsRef<str>>(&self, name: T) -> Result<mongodb::Database> {
+        let db = self.database_or_register(name.as_ref());
+        let done = self
+            .setup
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .entry((name.as_ref().to_string(), M::MODEL_NAME))
+            .or_default()
+            .clone();
 
-/// Initialize the database connection
+        let mut done = done.lock().await;
+        if !*done {
+            M::setup_in(db.clone()).await?;
+            *done = true;
+        }
+
+        Ok(db)
+    }
+
+    /// Check that the server is reachable
+    pub async fn ping(&self) -> Result<()> {
+        self.client
+            .database("admin")
+            .run_command(doc! {"ping": 1})
+            .await?;
+        Ok(())
+    }
+
+    /// Run the closure inside a transaction of this connection
+    ///
+    /// See [`transaction`](crate::transaction) for the retry behavior
+    pub async fn transaction<F, Fut, R>(&self, f: F) -> Result<R>
+    where
+        F: FnMut(Transaction) -> Fut,
+        Fut: Future<Output = Result<R>>,
+    {
+        crate::transaction::run(&self.client, f).await
+    }
+}
+
+/// Get the database of the model
 ///
-/// This function must be called before using the database
+/// The name overrides the database of the model and is resolved as a tenant database
+pub(crate) async fn model_database<M: Model>(name: Option<String>) -> Result<mongodb::Database> {
+    match name {
+        Some(name) => get_connection(M::CONNECTION)?.tenant::<M, _>(name).await,
+        None => M::get_database(),
+    }
+}
+
+/// CONNECTIONS stores the registered connections by name
+static CONNECTIONS: Lazy<RwLock<HashMap<String, Mangga>>> = Lazy::new(Default::default);
+
+/// Register the connection by name
+///
+/// Returns the connection previously registered with the same name
+pub fn register_connection<N: Into<String>>(name: N, mangga: Mangga) -> Option<Mangga> {
+    CONNECTIONS
+        .write()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(name.into(), mangga)
+}
+
+/// Remove the connection by name
+///
+/// The client is closed once every handle of the connection is dropped
+pub fn remove_connection<N: AsRef<str>>(name: N) -> Option<Mangga> {
+    CONNECTIONS
+        .write()
+        .unwrap_or_else(|e| e.into_inner())
+        .remove(name.as_ref())
+}
+
+/// Get the connection by name
+pub fn get_connection<N: AsRef<str>>(name: N) -> Result<Mangga> {
+    CONNECTIONS
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(name.as_ref())
+        .cloned()
+        .ok_or_else(|| Error::Init(format!("Connection `{}` is not registered", name.as_ref())))
+}
+
+/// Initialize the default database connection
+///
+/// This function must be called before using the models without connection attribute.
+/// Calling it again replaces the default connection.
 ///
 /// # Arguments
 ///
@@ -28,37 +179,56 @@ static MANGGA: OnceCell<Client> = OnceCell::new();
 /// ```no_run
 /// use mangga::prelude::*;
 ///
+/// # async fn run() -> mangga::Result<()> {
 /// let db_uri = "mongodb://localhost:27017";
 /// let databases = vec!["db1", "db2"];
-/// connect_database(db_uri, databases).await;
+/// connect_database(db_uri, databases).await?;
+/// # Ok(())
+/// # }
 /// ```
 pub async fn connect_database<U: AsRef<str>, D: IntoIterator<Item = S>, S: AsRef<str>>(
     uri: U,
     databases: D,
 ) -> Result<()> {
-    let client = mongodb::Client::with_uri_str(uri.as_ref()).await?;
-    let mut db = HashMap::new();
-    for db_name in databases.into_iter() {
-        db.insert(
-            db_name.as_ref().to_string(),
-            client.database(db_name.as_ref()),
-        );
-    }
+    let mangga = Mangga::connect(uri, databases).await?;
+    register_connection(DEFAULT_CONNECTION, mangga);
 
-    MANGGA
-        .set(Client { c: client, db })
-        .map_err(|_| Error::Init("Failed to set MANGGA".to_string()))?;
+    Ok(())
+}
+
+/// Initialize the default database connection from the config
+///
+/// Calling it again replaces the default connection.
+///
+/// # Examples
+///
+/// ```no_run
+/// use mangga::prelude::*;
+///
+/// # async fn run() -> mangga::Result<()> {
+/// connect_with(ConnectConfig::from_env()?).await?;
+/// ping().await?;
+/// # Ok(())
+/// # }
+/// ```
+pub async fn connect_with(config: ConnectConfig) -> Result<()> {
+    let mangga = config.connect().await?;
+    register_connection(DEFAULT_CONNECTION, mangga);
 
     Ok(())
 }
 
-/// Get the database
+/// Check that the server of the default connection is reachable
+pub async fn ping() -> Result<()> {
+    get_connection(DEFAULT_CONNECTION)?.ping().await
+}
+
+/// Get the database of the default connection
 pub fn get_database<T: AsRef<str>>(name: T) -> Result<mongodb::Database> {
-    MANGGA
-        .get()
-        .ok_or_else(|| Error::Init("Failed to get MANGGA".to_string()))?
-        .db
-        .get(name.as_ref())
-        .ok_or_else(|| Error::Init("Failed to get database".to_string()))
-        .cloned()
+    get_connection(DEFAULT_CONNECTION)?.database(name)
+}
+
+/// Get the client of the default connection
+pub fn get_client() -> Result<mongodb::Client> {
+    Ok(get_connection(DEFAULT_CONNECTION)?.client)
 }
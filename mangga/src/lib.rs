@@ -1,18 +1,72 @@
+mod config;
 mod db;
 pub(crate) mod operations;
 mod traits;
+mod transaction;
 mod types;
 
+#[cfg(test)]
+mod testing;
+
+pub use config::ConnectConfig;
+pub use db::Mangga;
+pub use transaction::{transaction, Transaction};
 pub use types::{Error, Result};
 pub use bson;
 
 pub mod prelude {
     pub use crate::{
-        db::{connect_database, get_database},
+        config::ConnectConfig,
+        db::{
+            connect_database,
+            connect_with,
+            get_client,
+            get_connection,
+            get_database,
+            ping,
+            register_connection,
+            remove_connection,
+            Mangga,
+            DEFAULT_CONNECTION,
+        },
+        operations::{
+            and,
+            nor,
+            not,
+            or,
+            Change,
+            ChangeEvent,
+            DateTrunc,
+            DateUnit,
+            Filter,
+            Group,
+            KeysetPage,
+            Page,
+            Sort,
+            Update,
+        },
         traits::*,
-        types::{is_id, ID, DateTime},
+        transaction::{transaction, Transaction},
+        types::{
+            is_id,
+            CappedSpec,
+            CollectionSpec,
+            DateTime,
+            DeleteResult,
+            IndexCollation,
+            IndexKey,
+            IndexPlan,
+            IndexPolicy,
+            IndexSpec,
+            InsertManyResult,
+            InsertOneResult,
+            ModelIndex,
+            TimeseriesSpec,
+            UpdateResult,
+            ID,
+        },
     };
     pub use bson;
-    pub use mangga_macro::Model;
+    pub use mangga_macro::{BsonSchema, Embedded, Model, Projection};
     pub use mongodb;
 }
@@ -0,0 +1,259 @@
+use super::{CursorStream, Group};
+use crate::{
+    db::model_database,
+    traits::{AsFilter, Field, FieldSet, IntoSort, Model},
+    types::BoxFut,
+    Error,
+    Result,
+    Transaction,
+};
+use bson::{doc, Document};
+use futures::{stream, TryStreamExt};
+use mongodb::options::AggregateOptions;
+use serde::Deserialize;
+use std::future::{Future, IntoFuture};
+
+/// Aggregate
+///
+/// Represents the aggregate operation
+pub struct Aggregate<M: Model, T = Document> {
+    pipeline: Vec<Document>,
+    opts: Option<AggregateOptions>,
+    session: Option<Transaction>,
+    db_name: Option<String>,
+    __marker: std::marker::PhantomData<(M, T)>,
+}
+
+impl<M: Model, T> Aggregate<M, T> {
+    /// Create a new aggregate operation
+    pub fn new(pipeline: Vec<Document>) -> Self {
+        Self {
+            pipeline,
+            opts: None,
+            session: None,
+            db_name: None,
+            __marker: std::marker::PhantomData,
+        }
+    }
+
+    /// Set the number of documents fetched per batch
+    pub fn batch_size(mut self, size: u32) -> Self {
+        self.opts.get_or_insert_with(Default::default).batch_size = Some(size);
+        self
+    }
+
+    /// Run the operation in the transaction
+    pub fn session(mut self, tx: &mut Transaction) -> Self {
+        self.session = Some(tx.clone());
+        self
+    }
+
+    /// Run the operation in the database chosen at runtime
+    ///
+    /// The database is registered on first use and the model is set up in it
+    pub fn in_db<N: Into<String>>(mut self, name: N) -> Self {
+        self.db_name = Some(name.into());
+        self
+    }
+
+    /// Append raw stage into the pipeline
+    pub fn stage(mut self, stage: Document) -> Self {
+        self.pipeline.push(stage);
+        self
+    }
+
+    /// Append `$match` stage
+    pub fn match_<F: AsFilter>(self, filter: F) -> Self {
+        self.stage(doc! {"$match": filter.as_filter()})
+    }
+
+    /// Append `$sort` stage
+    pub fn sort<S: IntoSort>(self, sort: S) -> Self {
+        self.stage(doc! {"$sort": sort.into_sort()})
+    }
+
+    /// Append `$limit` stage
+    pub fn limit(self, limit: i64) -> Self {
+        self.stage(doc! {"$limit": limit})
+    }
+
+    /// Append `$skip` stage
+    pub fn skip(self, skip: u64) -> Self {
+        self.stage(doc! {"$skip": skip as i64})
+    }
+
+    /// Append `$project` stage including the given fields
+    pub fn project<P: FieldSet<M>>(self, fields: P) -> Self {
+        let projection = fields
+            .names()
+            .into_iter()
+            .map(|name| (name.to_string(), 1.into()))
+            .collect::<Document>();
+        self.stage(doc! {"$project": projection})
+    }
+
+    /// Append `$unwind` stage of the given field
+    pub fn unwind<F: Field<Model = M>>(self, _field: F) -> Self {
+        let path = format!("${}", F::NAME);
+        self.stage(doc! {"$unwind": path})
+    }
+
+    /// Append `$group` stage
+    pub fn group(self, group: Group<M>) -> Self {
+        self.stage(doc! {"$group": Document::from(group)})
+    }
+
+    /// Append `$lookup` stage joining another model
+    ///
+    /// The result is stored in `as_field` as an array of the foreign model
+    pub fn lookup<L, R>(self, _local: L, _foreign: R, as_field: &str) -> Self
+    where
+        L: Field<Model = M>,
+        R: Field,
+    {
+        self.stage(doc! {
+            "$lookup": {
+                "from": <R::Model as Model>::MODEL_NAME,
+                "localField": L::NAME,
+                "foreignField": R::NAME,
+                "as": as_field,
+            }
+        })
+    }
+
+    /// Deserialize the result into another type
+    pub fn into<U>(self) -> Aggregate<M, U> {
+        Aggregate {
+            pipeline: self.pipeline,
+            opts: self.opts,
+            session: self.session,
+            db_name: self.db_name,
+            __marker: std::marker::PhantomData,
+        }
+    }
+
+    /// Get the pipeline
+    pub fn pipeline(&self) -> &[Document] {
+        &self.pipeline
+    }
+}
+
+impl<M: Model, T> Aggregate<M, T>
+where
+    T: for<'de> Deserialize<'de> + Send + Sync + 'static,
+{
+    /// Get the result as stream
+    ///
+    /// Documents are fetched lazily from the cursor instead of being collected into memory
+    pub fn stream(self) -> CursorStream<T> {
+        let opts = self.opts;
+        let pipeline = self.pipeline;
+        let session = self.session;
+        let db_name = self.db_name;
+        let cursor = async move {
+            let db = model_database::<M>(db_name).await?;
+            let col = db.collection::<M>(M::MODEL_NAME);
+            let action = col.aggregate(pipeline).with_options(opts).with_type::<T>();
+            let cursor = match session {
+                Some(tx) => {
+                    let cursor = action.session(&mut *tx.lock().await).await?;
+                    CursorStream::with_session(cursor, tx)
+                }
+                None => CursorStream::new(Box::pin(action.await?.map_err(Error::from))),
+            };
+            Ok::<_, Error>(cursor)
+        };
+
+        CursorStream::new(Box::pin(stream::once(cursor).try_flatten()))
+    }
+}
+
+impl<M: Model, T> IntoFuture for Aggregate<M, T>
+where
+    T: for<'de> Deserialize<'de> + Send + Sync + 'static,
+{
+    type IntoFuture = AggregateFuture<T>;
+    type Output = Result<Vec<T>>;
+
+    fn into_future(self) -> Self::IntoFuture {
+        let opts = self.opts;
+        let pipeline = self.pipeline;
+        let session = self.session;
+        let db_name = self.db_name;
+        AggregateFuture(Box::pin(async move {
+            let db = model_database::<M>(db_name).await?;
+            let col = db.collection::<M>(M::MODEL_NAME);
+            let action = col.aggregate(pipeline).with_options(opts).with_type::<T>();
+            let res = match session {
+                Some(tx) => {
+                    let mut session = tx.lock().await;
+                    let mut cursor = action.session(&mut *session).await?;
+                    cursor.stream(&mut session).try_collect::<Vec<_>>().await?
+                }
+                None => action.await?.try_collect::<Vec<_>>().await?,
+            };
+            Ok(res)
+        }))
+    }
+}
+
+/// AggregateFuture
+///
+/// Represents the executor of the aggregate operation
+pub struct AggregateFuture<T>(BoxFut<Vec<T>>);
+
+impl<T> Future for AggregateFuture<T> {
+    type Output = Result<Vec<T>>;
+
+    fn poll(
+        self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Self::Output> {
+        self.get_mut().0.as_mut().poll(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        testing::{Age, Author, Id, Name, Scores, User},
+        traits::{Queryable, SortAble},
+    };
+
+    #[test]
+    fn stages_are_appended_in_order() {
+        let aggregate = Aggregate::<User>::new(vec![])
+            .match_(Age.gte(18))
+            .sort(Age.desc())
+            .skip(20)
+            .limit(10)
+            .unwind(Scores)
+            .project((Name, Age));
+        assert_eq!(
+            aggregate.pipeline(),
+            [
+                doc! {"$match": {"age": {"$gte": 18}}},
+                doc! {"$sort": {"age": -1}},
+                doc! {"$skip": 20_i64},
+                doc! {"$limit": 10_i64},
+                doc! {"$unwind": "$scores"},
+                doc! {"$project": {"name": 1, "age": 1}},
+            ]
+        );
+    }
+
+    #[test]
+    fn lookup_joins_the_foreign_model() {
+        let aggregate = Aggregate::<User>::new(vec![]).lookup(Id, Author, "posts");
+        assert_eq!(
+            aggregate.pipeline(),
+            [doc! {"$lookup": {
+                "from": "posts",
+                "localField": "_id",
+                "foreignField": "author_id",
+                "as": "posts",
+            }}]
+        );
+    }
+}
@@ -1,7 +1,16 @@
-use crate::{db::get_database, traits::Model, types::BoxFut, Result};
-use bson::Document;
-use mongodb::options::{CountOptions, CountOptionsBuilder};
-use std::future::{Future, IntoFuture};
+use crate::{
+    db::model_database,
+    traits::Model,
+    types::{BoxFut, ModelIndex},
+    Result,
+    Transaction,
+};
+use bson::{Bson, Document};
+use mongodb::options::{Collation, CountOptions};
+use std::{
+    future::{Future, IntoFuture},
+    time::Duration,
+};
 
 /// Count
 ///
@@ -9,6 +18,8 @@ use std::future::{Future, IntoFuture};
 pub struct Count<M: Model> {
     filter: Document,
     opts: Option<CountOptions>,
+    session: Option<Transaction>,
+    db_name: Option<String>,
     __marker: std::marker::PhantomData<M>,
 }
 
@@ -18,16 +29,70 @@ impl<M: Model> Count<M> {
         Self {
             filter,
             opts: None,
+            session: None,
+            db_name: None,
             __marker: std::marker::PhantomData,
         }
     }
 
     /// Set count options
+    ///
+    /// Gives access to the driver options which are not covered by the builder methods
     pub fn opts<F>(mut self, f: F) -> Self
     where
-        F: FnOnce(CountOptionsBuilder<((), (), (), (), (), (), (), ())>) -> CountOptions,
+        F: FnOnce(&mut CountOptions),
     {
-        self.opts = Some(f(CountOptions::builder()));
+        f(self.opts.get_or_insert_with(Default::default));
+        self
+    }
+
+    /// Limit the number of documents
+    pub fn limit(mut self, limit: u64) -> Self {
+        self.opts.get_or_insert_with(Default::default).limit = Some(limit);
+        self
+    }
+
+    /// Skip the number of documents
+    pub fn skip(mut self, skip: u64) -> Self {
+        self.opts.get_or_insert_with(Default::default).skip = Some(skip);
+        self
+    }
+
+    /// Use the index of the model
+    pub fn hint(mut self, index: ModelIndex<M>) -> Self {
+        self.opts.get_or_insert_with(Default::default).hint = Some(index.into());
+        self
+    }
+
+    /// Set the maximum execution time
+    pub fn max_time(mut self, max_time: Duration) -> Self {
+        self.opts.get_or_insert_with(Default::default).max_time = Some(max_time);
+        self
+    }
+
+    /// Set the collation
+    pub fn collation(mut self, collation: Collation) -> Self {
+        self.opts.get_or_insert_with(Default::default).collation = Some(collation);
+        self
+    }
+
+    /// Attach comment to the operation
+    pub fn comment<C: Into<Bson>>(mut self, comment: C) -> Self {
+        self.opts.get_or_insert_with(Default::default).comment = Some(comment.into());
+        self
+    }
+
+    /// Run the operation in the transaction
+    pub fn session(mut self, tx: &mut Transaction) -> Self {
+        self.session = Some(tx.clone());
+        self
+    }
+
+    /// Run the operation in the database chosen at runtime
+    ///
+    /// The database is registered on first use and the model is set up in it
+    pub fn in_db<N: Into<String>>(mut self, name: N) -> Self {
+        self.db_name = Some(name.into());
         self
     }
 }
@@ -38,11 +103,17 @@ impl<M: Model> IntoFuture for Count<M> {
 
     fn into_future(self) -> Self::IntoFuture {
         let opts = self.opts;
+        let session = self.session;
+        let db_name = self.db_name;
         let filter = self.filter;
         CountFuture(Box::pin(async move {
-            let db = get_database(M::DB_NAME)?;
+            let db = model_database::<M>(db_name).await?;
             let col = db.collection::<M>(M::MODEL_NAME);
-            let res = col.count_documents(filter).with_options(opts).await?;
+            let action = col.count_documents(filter).with_options(opts);
+            let res = match session {
+                Some(tx) => action.session(&mut *tx.lock().await).await?,
+                None => action.await?,
+            };
             Ok(res as usize)
         }))
     }
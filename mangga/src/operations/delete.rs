@@ -1,6 +1,12 @@
-use crate::{db::get_database, traits::Model, types::BoxFut, Result};
-use bson::Document;
-use mongodb::options::{DeleteOptions, DeleteOptionsBuilder};
+use crate::{
+    db::model_database,
+    traits::Model,
+    types::{BoxFut, DeleteResult, ModelIndex},
+    Result,
+    Transaction,
+};
+use bson::{Bson, Document};
+use mongodb::options::{Collation, DeleteOptions};
 use std::future::{Future, IntoFuture};
 
 /// DeleteOne
@@ -9,6 +15,8 @@ use std::future::{Future, IntoFuture};
 pub struct DeleteOne<M: Model> {
     filter: Document,
     opts: Option<DeleteOptions>,
+    session: Option<Transaction>,
+    db_name: Option<String>,
     __marker: std::marker::PhantomData<M>,
 }
 
@@ -18,32 +26,74 @@ impl<M: Model> DeleteOne<M> {
         Self {
             filter,
             opts: None,
+            session: None,
+            db_name: None,
             __marker: std::marker::PhantomData,
         }
     }
 
     /// Set delete one options
+    ///
+    /// Gives access to the driver options which are not covered by the builder methods
     pub fn opts<F>(mut self, f: F) -> Self
     where
-        F: FnOnce(DeleteOptionsBuilder<((), (), (), (), ())>) -> DeleteOptions,
+        F: FnOnce(&mut DeleteOptions),
     {
-        self.opts = Some(f(DeleteOptions::builder()));
+        f(self.opts.get_or_insert_with(Default::default));
+        self
+    }
+
+    /// Use the index of the model
+    pub fn hint(mut self, index: ModelIndex<M>) -> Self {
+        self.opts.get_or_insert_with(Default::default).hint = Some(index.into());
+        self
+    }
+
+    /// Set the collation
+    pub fn collation(mut self, collation: Collation) -> Self {
+        self.opts.get_or_insert_with(Default::default).collation = Some(collation);
+        self
+    }
+
+    /// Attach comment to the operation
+    pub fn comment<C: Into<Bson>>(mut self, comment: C) -> Self {
+        self.opts.get_or_insert_with(Default::default).comment = Some(comment.into());
+        self
+    }
+
+    /// Run the operation in the transaction
+    pub fn session(mut self, tx: &mut Transaction) -> Self {
+        self.session = Some(tx.clone());
+        self
+    }
+
+    /// Run the operation in the database chosen at runtime
+    ///
+    /// The database is registered on first use and the model is set up in it
+    pub fn in_db<N: Into<String>>(mut self, name: N) -> Self {
+        self.db_name = Some(name.into());
         self
     }
 }
 
 impl<M: Model> IntoFuture for DeleteOne<M> {
-    type Output = Result<()>;
+    type Output = Result<DeleteResult>;
     type IntoFuture = DeleteOneFuture;
 
     fn into_future(self) -> Self::IntoFuture {
         let opts = self.opts;
         let filter = self.filter;
+        let session = self.session;
+        let db_name = self.db_name;
         DeleteOneFuture(Box::pin(async move {
-            let db = get_database(M::DB_NAME)?;
+            let db = model_database::<M>(db_name).await?;
             let col = db.collection::<M>(M::MODEL_NAME);
-            col.delete_one(filter).with_options(opts).await?;
-            Ok(())
+            let action = col.delete_one(filter).with_options(opts);
+            let res = match session {
+                Some(tx) => action.session(&mut *tx.lock().await).await?,
+                None => action.await?,
+            };
+            Ok(res.into())
         }))
     }
 }
@@ -51,10 +101,10 @@ impl<M: Model> IntoFuture for DeleteOne<M> {
 /// DeleteOneFuture
 ///
 /// Represents the future of the delete one operation
-pub struct DeleteOneFuture(BoxFut<()>);
+pub struct DeleteOneFuture(BoxFut<DeleteResult>);
 
 impl Future for DeleteOneFuture {
-    type Output = Result<()>;
+    type Output = Result<DeleteResult>;
 
     fn poll(
         self: std::pin::Pin<&mut Self>,
@@ -70,6 +120,8 @@ impl Future for DeleteOneFuture {
 pub struct DeleteMany<M: Model> {
     filter: Document,
     opts: Option<DeleteOptions>,
+    session: Option<Transaction>,
+    db_name: Option<String>,
     __marker: std::marker::PhantomData<M>,
 }
 
@@ -79,32 +131,74 @@ impl<M: Model> DeleteMany<M> {
         Self {
             filter,
             opts: None,
+            session: None,
+            db_name: None,
             __marker: std::marker::PhantomData,
         }
     }
 
     /// Set delete many options
+    ///
+    /// Gives access to the driver options which are not covered by the builder methods
     pub fn opts<F>(mut self, f: F) -> Self
     where
-        F: FnOnce(DeleteOptionsBuilder<((), (), (), (), ())>) -> DeleteOptions,
+        F: FnOnce(&mut DeleteOptions),
     {
-        self.opts = Some(f(DeleteOptions::builder()));
+        f(self.opts.get_or_insert_with(Default::default));
+        self
+    }
+
+    /// Use the index of the model
+    pub fn hint(mut self, index: ModelIndex<M>) -> Self {
+        self.opts.get_or_insert_with(Default::default).hint = Some(index.into());
+        self
+    }
+
+    /// Set the collation
+    pub fn collation(mut self, collation: Collation) -> Self {
+        self.opts.get_or_insert_with(Default::default).collation = Some(collation);
+        self
+    }
+
+    /// Attach comment to the operation
+    pub fn comment<C: Into<Bson>>(mut self, comment: C) -> Self {
+        self.opts.get_or_insert_with(Default::default).comment = Some(comment.into());
+        self
+    }
+
+    /// Run the operation in the transaction
+    pub fn session(mut self, tx: &mut Transaction) -> Self {
+        self.session = Some(tx.clone());
+        self
+    }
+
+    /// Run the operation in the database chosen at runtime
+    ///
+    /// The database is registered on first use and the model is set up in it
+    pub fn in_db<N: Into<String>>(mut self, name: N) -> Self {
+        self.db_name = Some(name.into());
         self
     }
 }
 
 impl<M: Model> IntoFuture for DeleteMany<M> {
-    type Output = Result<()>;
+    type Output = Result<DeleteResult>;
     type IntoFuture = DeleteManyFuture;
 
     fn into_future(self) -> Self::IntoFuture {
         let opts = self.opts;
         let filter = self.filter;
+        let session = self.session;
+        let db_name = self.db_name;
         DeleteManyFuture(Box::pin(async move {
-            let db = get_database(M::DB_NAME)?;
+            let db = model_database::<M>(db_name).await?;
             let col = db.collection::<M>(M::MODEL_NAME);
-            col.delete_many(filter).with_options(opts).await?;
-            Ok(())
+            let action = col.delete_many(filter).with_options(opts);
+            let res = match session {
+                Some(tx) => action.session(&mut *tx.lock().await).await?,
+                None => action.await?,
+            };
+            Ok(res.into())
         }))
     }
 }
@@ -112,10 +206,10 @@ impl<M: Model> IntoFuture for DeleteMany<M> {
 /// DeleteManyFuture
 ///
 /// Represents the future of the delete many operation
-pub struct DeleteManyFuture(BoxFut<()>);
+pub struct DeleteManyFuture(BoxFut<DeleteResult>);
 
 impl Future for DeleteManyFuture {
-    type Output = Result<()>;
+    type Output = Result<DeleteResult>;
 
     fn poll(
         self: std::pin::Pin<&mut Self>,
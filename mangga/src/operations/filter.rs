@@ -1,5 +1,6 @@
-use crate::traits::{AsFilter, Field, Queryable};
+use crate::traits::{AsFilter, Field, Model, ModelFilter, Queryable};
 use bson::{doc, Bson, Document};
+use std::ops::{BitAnd, BitOr, Not};
 
 // Define the query operators
 pub enum Operator {
@@ -11,6 +12,9 @@ pub enum Operator {
     Ne,
     In,
     Nin,
+    All,
+    Size,
+    ElemMatch,
 }
 
 impl Operator {
@@ -25,6 +29,9 @@ impl Operator {
             Operator::Ne => "$ne",
             Operator::In => "$in",
             Operator::Nin => "$nin",
+            Operator::All => "$all",
+            Operator::Size => "$size",
+            Operator::ElemMatch => "$elemMatch",
         }
     }
 }
@@ -55,11 +62,14 @@ where
     V: Into<Bson>,
 {
     fn from(value: Query<F, V>) -> Self {
-        let name = F::NAME;
         let op = value.op.as_str();
         let v = value.v;
-        doc! {
-            name: {op: v}
+        // an array element has no name, its query is the operator alone
+        match F::NAME {
+            "" => doc! {op: v},
+            name => doc! {
+                name: {op: v}
+            },
         }
     }
 }
@@ -70,12 +80,7 @@ where
     V: Into<Bson>,
 {
     fn from(value: Query<F, V>) -> Self {
-        let name = F::NAME;
-        let op = value.op.as_str();
-        let v = value.v;
-        Bson::Document(doc! {
-            name: {op: v}
-        })
+        Bson::Document(value.into())
     }
 }
 
@@ -90,6 +95,212 @@ where
 }
 
 
+impl<F, V> ModelFilter for Query<F, V>
+where
+    F: Field,
+    V: Into<Bson>,
+{
+    type Model = F::Model;
+}
+
+impl<F, V, R> BitAnd<R> for Query<F, V>
+where
+    F: Field,
+    V: Into<Bson>,
+    R: ModelFilter<Model = F::Model>,
+{
+    type Output = Filter<F::Model>;
+
+    fn bitand(self, rhs: R) -> Self::Output {
+        and(self, rhs)
+    }
+}
+
+impl<F, V, R> BitOr<R> for Query<F, V>
+where
+    F: Field,
+    V: Into<Bson>,
+    R: ModelFilter<Model = F::Model>,
+{
+    type Output = Filter<F::Model>;
+
+    fn bitor(self, rhs: R) -> Self::Output {
+        or(self, rhs)
+    }
+}
+
+impl<F, V> Not for Query<F, V>
+where
+    F: Field,
+    V: Into<Bson>,
+{
+    type Output = Filter<F::Model>;
+
+    fn not(self) -> Self::Output {
+        not(self)
+    }
+}
+
+/// Filter
+///
+/// Represents a combined filter of a model
+#[derive(Debug, Clone)]
+pub struct Filter<M: Model> {
+    doc: Document,
+    __marker: std::marker::PhantomData<M>,
+}
+
+impl<M: Model> Filter<M> {
+    /// Create a filter from raw document
+    pub fn raw(doc: Document) -> Self {
+        Self {
+            doc,
+            __marker: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<M: Model> From<Document> for Filter<M> {
+    fn from(value: Document) -> Self {
+        Self::raw(value)
+    }
+}
+
+impl<M: Model> From<Filter<M>> for Document {
+    fn from(value: Filter<M>) -> Self {
+        value.doc
+    }
+}
+
+impl<M: Model> AsFilter for Filter<M> {
+    fn as_filter(self) -> Document {
+        self.doc
+    }
+}
+
+impl<M: Model> ModelFilter for Filter<M> {
+    type Model = M;
+}
+
+impl<M, R> BitAnd<R> for Filter<M>
+where
+    M: Model,
+    R: ModelFilter<Model = M>,
+{
+    type Output = Filter<M>;
+
+    fn bitand(self, rhs: R) -> Self::Output {
+        and(self, rhs)
+    }
+}
+
+impl<M, R> BitOr<R> for Filter<M>
+where
+    M: Model,
+    R: ModelFilter<Model = M>,
+{
+    type Output = Filter<M>;
+
+    fn bitor(self, rhs: R) -> Self::Output {
+        or(self, rhs)
+    }
+}
+
+impl<M: Model> Not for Filter<M> {
+    type Output = Filter<M>;
+
+    fn not(self) -> Self::Output {
+        not(self)
+    }
+}
+
+/// Push a filter into a logical clause, flattening nested clauses of the same operator
+fn push_clause(op: &str, doc: Document, clauses: &mut Vec<Bson>) {
+    if doc.len() == 1 {
+        if let Some(Bson::Array(inner)) = doc.get(op) {
+            clauses.extend(inner.iter().cloned());
+            return;
+        }
+    }
+
+    // an empty filter matches everything, so it has no effect inside `$and`
+    if doc.is_empty() && op == "$and" {
+        return;
+    }
+
+    clauses.push(Bson::Document(doc));
+}
+
+/// Create `and` filter
+///
+/// Nested `$and` clauses are flattened into a single one
+pub fn and<L, R>(lhs: L, rhs: R) -> Filter<L::Model>
+where
+    L: ModelFilter,
+    R: ModelFilter<Model = L::Model>,
+{
+    let mut clauses = vec![];
+    push_clause("$and", lhs.as_filter(), &mut clauses);
+    push_clause("$and", rhs.as_filter(), &mut clauses);
+
+    let doc = match clauses.len() {
+        0 => doc! {},
+        1 => match clauses.pop() {
+            Some(Bson::Document(doc)) => doc,
+            _ => doc! {},
+        },
+        _ => doc! {"$and": clauses},
+    };
+
+    Filter::raw(doc)
+}
+
+/// Create `or` filter
+///
+/// Nested `$or` clauses are flattened into a single one
+pub fn or<L, R>(lhs: L, rhs: R) -> Filter<L::Model>
+where
+    L: ModelFilter,
+    R: ModelFilter<Model = L::Model>,
+{
+    let mut clauses = vec![];
+    push_clause("$or", lhs.as_filter(), &mut clauses);
+    push_clause("$or", rhs.as_filter(), &mut clauses);
+
+    Filter::raw(doc! {"$or": clauses})
+}
+
+/// Create `nor` filter
+pub fn nor<L, R>(lhs: L, rhs: R) -> Filter<L::Model>
+where
+    L: ModelFilter,
+    R: ModelFilter<Model = L::Model>,
+{
+    Filter::raw(doc! {"$nor": [lhs.as_filter(), rhs.as_filter()]})
+}
+
+/// Create `not` filter
+///
+/// Single field queries are negated with `$not`, anything else is wrapped in `$nor`
+pub fn not<F: ModelFilter>(filter: F) -> Filter<F::Model> {
+    let doc = filter.as_filter();
+
+    if doc.len() == 1 {
+        if let Some((name, Bson::Document(query))) = doc.iter().next() {
+            if !name.starts_with('$')
+                && !query.is_empty()
+                && query.keys().all(|k| k.starts_with('$'))
+            {
+                let name = name.to_string();
+                let query = query.clone();
+                return Filter::raw(doc! {name: {"$not": query}});
+            }
+        }
+    }
+
+    Filter::raw(doc! {"$nor": [doc]})
+}
+
 impl<T> Queryable for T where T: Field {}
 
 impl AsFilter for () {
@@ -102,4 +313,127 @@ impl AsFilter for Document {
     fn as_filter(self) -> Document {
         self
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{Age, Name, Scores, User};
+
+    #[test]
+    fn and_flattens_nested_clauses() {
+        let filter = (Name.eq("a") & Age.gt(1)) & (Age.lt(9) & Name.ne("b"));
+        assert_eq!(
+            filter.as_filter(),
+            doc! {"$and": [
+                {"name": {"$eq": "a"}},
+                {"age": {"$gt": 1}},
+                {"age": {"$lt": 9}},
+                {"name": {"$ne": "b"}},
+            ]}
+        );
+    }
+
+    #[test]
+    fn and_drops_empty_filters() {
+        let filter = and(Filter::<User>::raw(doc! {}), Name.eq("a"));
+        assert_eq!(filter.as_filter(), doc! {"name": {"$eq": "a"}});
+
+        let filter = and(Filter::<User>::raw(doc! {}), Filter::<User>::raw(doc! {}));
+        assert_eq!(filter.as_filter(), doc! {});
+    }
+
+    #[test]
+    fn or_flattens_nested_clauses_only() {
+        let filter = (Name.eq("a") | Age.gt(1)) | Age.lt(0);
+        assert_eq!(
+            filter.as_filter(),
+            doc! {"$or": [
+                {"name": {"$eq": "a"}},
+                {"age": {"$gt": 1}},
+                {"age": {"$lt": 0}},
+            ]}
+        );
+
+        // clauses of another operator are kept as they are
+        let filter = (Name.eq("a") | Age.gt(1)) & Age.lt(0);
+        assert_eq!(
+            filter.as_filter(),
+            doc! {"$and": [
+                {"$or": [{"name": {"$eq": "a"}}, {"age": {"$gt": 1}}]},
+                {"age": {"$lt": 0}},
+            ]}
+        );
+    }
+
+    #[test]
+    fn push_clause_keeps_documents_with_other_keys() {
+        let mut clauses = vec![];
+        push_clause("$and", doc! {"$and": [{"a": 1}], "b": 2}, &mut clauses);
+        assert_eq!(
+            clauses,
+            vec![Bson::Document(doc! {"$and": [{"a": 1}], "b": 2})]
+        );
+    }
+
+    #[test]
+    fn push_clause_keeps_operators_without_clause_list() {
+        let filter = and(Filter::<User>::raw(doc! {"$and": {"name": "a"}}), Age.gt(1));
+        assert_eq!(
+            filter.as_filter(),
+            doc! {"$and": [{"$and": {"name": "a"}}, {"age": {"$gt": 1}}]}
+        );
+
+        let filter = or(Filter::<User>::raw(doc! {"$or": "a"}), Age.gt(1));
+        assert_eq!(
+            filter.as_filter(),
+            doc! {"$or": [{"$or": "a"}, {"age": {"$gt": 1}}]}
+        );
+    }
+
+    #[test]
+    fn not_negates_single_field_queries() {
+        assert_eq!((!Age.gt(1)).as_filter(), doc! {"age": {"$not": {"$gt": 1}}});
+    }
+
+    #[test]
+    fn not_wraps_other_filters_in_nor() {
+        assert_eq!(
+            (!(Name.eq("a") & Age.gt(1))).as_filter(),
+            doc! {"$nor": [{"$and": [{"name": {"$eq": "a"}}, {"age": {"$gt": 1}}]}]}
+        );
+        assert_eq!(
+            not(Filter::<User>::raw(doc! {"name": "a"})).as_filter(),
+            doc! {"$nor": [{"name": "a"}]}
+        );
+    }
+
+    #[test]
+    fn array_queries_match_the_elements() {
+        assert_eq!(Scores.contains(3).as_filter(), doc! {"scores": {"$eq": 3}});
+        assert_eq!(
+            Scores.contains_any([1, 2]).as_filter(),
+            doc! {"scores": {"$in": [1, 2]}}
+        );
+        assert_eq!(
+            Scores.all(vec![1, 2]).as_filter(),
+            doc! {"scores": {"$all": [1, 2]}}
+        );
+        assert_eq!(
+            Scores.size(2).as_filter(),
+            doc! {"scores": {"$size": 2_i64}}
+        );
+    }
+
+    #[test]
+    fn elem_match_of_scalar_elements_uses_the_operator_alone() {
+        assert_eq!(
+            Scores.elem_match(|score| score.eq(3)).as_filter(),
+            doc! {"scores": {"$elemMatch": {"$eq": 3}}}
+        );
+        assert_eq!(
+            Scores.elem_match(|score| score.gt(3)).as_filter(),
+            doc! {"scores": {"$elemMatch": {"$gt": 3}}}
+        );
+    }
+}
@@ -1,89 +1,161 @@
-use crate::{db::get_database, traits::Model, types::BoxFut, Error, Result};
-use bson::Document;
-use futures::TryStreamExt;
-use mongodb::options::{FindOneOptions, FindOneOptionsBuilder, FindOptions, FindOptionsBuilder};
+use super::{CursorStream, Keyset, Paginate};
+use crate::{
+    db::model_database,
+    traits::{IntoSort, Model, Projection},
+    types::{BoxFut, ModelIndex},
+    Error,
+    Result,
+    Transaction,
+};
+use bson::{Bson, Document};
+use futures::{stream, TryStreamExt};
+use mongodb::options::{Collation, FindOneOptions, FindOptions};
 use serde::Deserialize;
-use std::future::{Future, IntoFuture};
+use std::{
+    future::{Future, IntoFuture},
+    time::Duration,
+};
 
 /// FindOne
 ///
 /// Represents the find one operation
-pub struct FindOne<M: Model> {
+pub struct FindOne<M: Model, T = M> {
     filter: Document,
     opts: Option<FindOneOptions>,
-    __marker: std::marker::PhantomData<M>,
+    session: Option<Transaction>,
+    db_name: Option<String>,
+    __marker: std::marker::PhantomData<(M, T)>,
 }
 
-impl<M: Model> FindOne<M> {
+impl<M: Model, T> FindOne<M, T> {
     /// Create a new find one operation
     pub fn new(filter: Document) -> Self {
         Self {
             filter,
             opts: None,
+            session: None,
+            db_name: None,
             __marker: std::marker::PhantomData,
         }
     }
 
     /// Set find one options
+    ///
+    /// Gives access to the driver options which are not covered by the builder methods
     pub fn opts<F>(mut self, f: F) -> Self
     where
-        F: FnOnce(
-            FindOneOptionsBuilder<(
-                (),
-                (),
-                (),
-                (),
-                (),
-                (),
-                (),
-                (),
-                (),
-                (),
-                (),
-                (),
-                (),
-                (),
-                (),
-                (),
-            )>,
-        ) -> FindOneOptions,
+        F: FnOnce(&mut FindOneOptions),
     {
-        self.opts = Some(f(FindOneOptions::builder()));
+        f(self.opts.get_or_insert_with(Default::default));
         self
     }
+
+    /// Skip the number of documents
+    pub fn skip(mut self, skip: u64) -> Self {
+        self.opts.get_or_insert_with(Default::default).skip = Some(skip);
+        self
+    }
+
+    /// Use the index of the model
+    pub fn hint(mut self, index: ModelIndex<M>) -> Self {
+        self.opts.get_or_insert_with(Default::default).hint = Some(index.into());
+        self
+    }
+
+    /// Set the maximum execution time
+    pub fn max_time(mut self, max_time: Duration) -> Self {
+        self.opts.get_or_insert_with(Default::default).max_time = Some(max_time);
+        self
+    }
+
+    /// Set the collation
+    pub fn collation(mut self, collation: Collation) -> Self {
+        self.opts.get_or_insert_with(Default::default).collation = Some(collation);
+        self
+    }
+
+    /// Attach comment to the operation
+    pub fn comment<C: Into<Bson>>(mut self, comment: C) -> Self {
+        self.opts.get_or_insert_with(Default::default).comment = Some(comment.into());
+        self
+    }
+
+    /// Run the operation in the transaction
+    pub fn session(mut self, tx: &mut Transaction) -> Self {
+        self.session = Some(tx.clone());
+        self
+    }
+
+    /// Run the operation in the database chosen at runtime
+    ///
+    /// The database is registered on first use and the model is set up in it
+    pub fn in_db<N: Into<String>>(mut self, name: N) -> Self {
+        self.db_name = Some(name.into());
+        self
+    }
+
+    /// Set the sort keys
+    pub fn sort<S: IntoSort>(mut self, sort: S) -> Self {
+        self.opts.get_or_insert_with(Default::default).sort = Some(sort.into_sort());
+        self
+    }
+
+    /// Select only the fields of the projection
+    pub fn select<P: Projection<Model = M>>(mut self) -> FindOne<M, P> {
+        self.opts.get_or_insert_with(Default::default).projection = Some(P::projection());
+        FindOne {
+            filter: self.filter,
+            opts: self.opts,
+            session: self.session,
+            db_name: self.db_name,
+            __marker: std::marker::PhantomData,
+        }
+    }
 }
 
-impl<M: Model> FindOne<M>
+impl<M: Model, T> FindOne<M, T>
 where
-    M: for<'de> Deserialize<'de>,
+    T: for<'de> Deserialize<'de> + Send + Sync + 'static,
 {
     /// Get optional result
-    pub fn optional(self) -> BoxFut<Option<M>> {
+    pub fn optional(self) -> BoxFut<Option<T>> {
         let opts = self.opts;
         let filter = self.filter;
+        let session = self.session;
+        let db_name = self.db_name;
         Box::pin(async move {
-            let db = get_database(M::DB_NAME)?;
-            let col = db.collection(M::MODEL_NAME);
-            let res = col.find_one(filter).with_options(opts).await?;
+            let db = model_database::<M>(db_name).await?;
+            let col = db.collection::<T>(M::MODEL_NAME);
+            let action = col.find_one(filter).with_options(opts);
+            let res = match session {
+                Some(tx) => action.session(&mut *tx.lock().await).await?,
+                None => action.await?,
+            };
             Ok(res)
         })
     }
 }
 
-impl<M: Model> IntoFuture for FindOne<M>
+impl<M: Model, T> IntoFuture for FindOne<M, T>
 where
-    M: for<'de> Deserialize<'de>,
+    T: for<'de> Deserialize<'de> + Send + Sync + 'static,
 {
-    type IntoFuture = FindOneFuture<M>;
-    type Output = Result<M>;
+    type IntoFuture = FindOneFuture<T>;
+    type Output = Result<T>;
 
     fn into_future(self) -> Self::IntoFuture {
         let opts = self.opts;
         let filter = self.filter;
+        let session = self.session;
+        let db_name = self.db_name;
         FindOneFuture(Box::pin(async move {
-            let db = get_database(M::DB_NAME)?;
-            let col = db.collection(M::MODEL_NAME);
-            let res = col.find_one(filter).with_options(opts).await?;
+            let db = model_database::<M>(db_name).await?;
+            let col = db.collection::<T>(M::MODEL_NAME);
+            let action = col.find_one(filter).with_options(opts);
+            let res = match session {
+                Some(tx) => action.session(&mut *tx.lock().await).await?,
+                None => action.await?,
+            };
             if let Some(res) = res {
                 Ok(res)
             } else {
@@ -96,10 +168,10 @@ where
 /// FindOneFuture
 ///
 /// Represents the future of the find one operation
-pub struct FindOneFuture<M: Model>(BoxFut<M>);
+pub struct FindOneFuture<T>(BoxFut<T>);
 
-impl<M: Model> Future for FindOneFuture<M> {
-    type Output = Result<M>;
+impl<T> Future for FindOneFuture<T> {
+    type Output = Result<T>;
 
     fn poll(
         self: std::pin::Pin<&mut Self>,
@@ -112,72 +184,190 @@ impl<M: Model> Future for FindOneFuture<M> {
 /// FindMany
 ///
 /// Represents the find many operation
-pub struct FindMany<M: Model> {
-    filter: Document,
-    opts: Option<FindOptions>,
-    __marker: std::marker::PhantomData<M>,
+pub struct FindMany<M: Model, T = M> {
+    pub(crate) filter: Document,
+    pub(crate) opts: Option<FindOptions>,
+    pub(crate) session: Option<Transaction>,
+    pub(crate) db_name: Option<String>,
+    __marker: std::marker::PhantomData<(M, T)>,
 }
 
-impl<M: Model> FindMany<M> {
+impl<M: Model, T> FindMany<M, T> {
     /// Create a new find many operation
     pub fn new(filter: Document) -> Self {
         Self {
             filter,
             opts: None,
+            session: None,
+            db_name: None,
             __marker: std::marker::PhantomData,
         }
     }
 
     /// Set find many options
+    ///
+    /// Gives access to the driver options which are not covered by the builder methods
     pub fn opts<F>(mut self, f: F) -> Self
     where
-        F: FnOnce(
-            FindOptionsBuilder<(
-                (),
-                (),
-                (),
-                (),
-                (),
-                (),
-                (),
-                (),
-                (),
-                (),
-                (),
-                (),
-                (),
-                (),
-                (),
-                (),
-                (),
-                (),
-                (),
-                (),
-                (),
-                (),
-            )>,
-        ) -> FindOptions,
+        F: FnOnce(&mut FindOptions),
     {
-        self.opts = Some(f(FindOptions::builder()));
+        f(self.opts.get_or_insert_with(Default::default));
+        self
+    }
+
+    /// Limit the number of documents
+    pub fn limit(mut self, limit: i64) -> Self {
+        self.opts.get_or_insert_with(Default::default).limit = Some(limit);
+        self
+    }
+
+    /// Skip the number of documents
+    pub fn skip(mut self, skip: u64) -> Self {
+        self.opts.get_or_insert_with(Default::default).skip = Some(skip);
         self
     }
+
+    /// Use the index of the model
+    pub fn hint(mut self, index: ModelIndex<M>) -> Self {
+        self.opts.get_or_insert_with(Default::default).hint = Some(index.into());
+        self
+    }
+
+    /// Set the maximum execution time
+    pub fn max_time(mut self, max_time: Duration) -> Self {
+        self.opts.get_or_insert_with(Default::default).max_time = Some(max_time);
+        self
+    }
+
+    /// Set the collation
+    pub fn collation(mut self, collation: Collation) -> Self {
+        self.opts.get_or_insert_with(Default::default).collation = Some(collation);
+        self
+    }
+
+    /// Attach comment to the operation
+    pub fn comment<C: Into<Bson>>(mut self, comment: C) -> Self {
+        self.opts.get_or_insert_with(Default::default).comment = Some(comment.into());
+        self
+    }
+
+    /// Run the operation in the transaction
+    pub fn session(mut self, tx: &mut Transaction) -> Self {
+        self.session = Some(tx.clone());
+        self
+    }
+
+    /// Run the operation in the database chosen at runtime
+    ///
+    /// The database is registered on first use and the model is set up in it
+    pub fn in_db<N: Into<String>>(mut self, name: N) -> Self {
+        self.db_name = Some(name.into());
+        self
+    }
+
+    /// Set the number of documents fetched per batch
+    pub fn batch_size(mut self, size: u32) -> Self {
+        self.opts.get_or_insert_with(Default::default).batch_size = Some(size);
+        self
+    }
+
+    /// Set the sort keys
+    pub fn sort<S: IntoSort>(mut self, sort: S) -> Self {
+        self.opts.get_or_insert_with(Default::default).sort = Some(sort.into_sort());
+        self
+    }
+
+    /// Select only the fields of the projection
+    pub fn select<P: Projection<Model = M>>(mut self) -> FindMany<M, P> {
+        self.opts.get_or_insert_with(Default::default).projection = Some(P::projection());
+        FindMany {
+            filter: self.filter,
+            opts: self.opts,
+            session: self.session,
+            db_name: self.db_name,
+            __marker: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<M: Model, T> FindMany<M, T>
+where
+    T: for<'de> Deserialize<'de> + Send + Sync + 'static,
+{
+    /// Paginate the result by keyset starting from the first page
+    ///
+    /// The sort keys are used as the range with `_id` as tie-breaker
+    pub fn keyset(self) -> Keyset<M, T> {
+        Keyset::new(self)
+    }
+
+    /// Paginate the result by keyset after the cursor
+    pub fn after<C: Into<String>>(self, cursor: C) -> Keyset<M, T> {
+        Keyset::new(self).after(cursor)
+    }
+
+    /// Paginate the result by keyset before the cursor
+    pub fn before<C: Into<String>>(self, cursor: C) -> Keyset<M, T> {
+        Keyset::new(self).before(cursor)
+    }
+
+    /// Paginate the result by page number, starts from 1
+    ///
+    /// The total number of documents is counted with the same filter
+    pub fn paginate(self, page: usize, per_page: usize) -> Paginate<M, T> {
+        Paginate::new(self, page, per_page)
+    }
+
+    /// Get the result as stream
+    ///
+    /// Documents are fetched lazily from the cursor instead of being collected into memory
+    pub fn stream(self) -> CursorStream<T> {
+        let opts = self.opts;
+        let filter = self.filter;
+        let session = self.session;
+        let db_name = self.db_name;
+        let cursor = async move {
+            let db = model_database::<M>(db_name).await?;
+            let col = db.collection::<T>(M::MODEL_NAME);
+            let action = col.find(filter).with_options(opts);
+            let cursor = match session {
+                Some(tx) => {
+                    let cursor = action.session(&mut *tx.lock().await).await?;
+                    CursorStream::with_session(cursor, tx)
+                }
+                None => CursorStream::new(Box::pin(action.await?.map_err(Error::from))),
+            };
+            Ok::<_, Error>(cursor)
+        };
+
+        CursorStream::new(Box::pin(stream::once(cursor).try_flatten()))
+    }
 }
 
-impl<M: Model> IntoFuture for FindMany<M>
+impl<M: Model, T> IntoFuture for FindMany<M, T>
 where
-    M: for<'de> Deserialize<'de>,
+    T: for<'de> Deserialize<'de> + Send + Sync + 'static,
 {
-    type IntoFuture = FindManyFuture<M>;
-    type Output = Result<Vec<M>>;
+    type IntoFuture = FindManyFuture<T>;
+    type Output = Result<Vec<T>>;
 
     fn into_future(self) -> Self::IntoFuture {
         let opts = self.opts;
         let filter = self.filter;
+        let session = self.session;
+        let db_name = self.db_name;
         FindManyFuture(Box::pin(async move {
-            let db = get_database(M::DB_NAME)?;
-            let col = db.collection(M::MODEL_NAME);
-            let res = col.find(filter).with_options(opts).await?;
-            let res = res.try_collect::<Vec<_>>().await?;
+            let db = model_database::<M>(db_name).await?;
+            let col = db.collection::<T>(M::MODEL_NAME);
+            let action = col.find(filter).with_options(opts);
+            let res = match session {
+                Some(tx) => {
+                    let mut session = tx.lock().await;
+                    let mut cursor = action.session(&mut *session).await?;
+                    cursor.stream(&mut session).try_collect::<Vec<_>>().await?
+                }
+                None => action.await?.try_collect::<Vec<_>>().await?,
+            };
             Ok(res)
         }))
     }
@@ -186,10 +376,10 @@ where
 /// FindManyFuture
 ///
 /// Represents the future of the find many operation
-pub struct FindManyFuture<M: Model>(BoxFut<Vec<M>>);
+pub struct FindManyFuture<T>(BoxFut<Vec<T>>);
 
-impl<M: Model> Future for FindManyFuture<M> {
-    type Output = Result<Vec<M>>;
+impl<T> Future for FindManyFuture<T> {
+    type Output = Result<Vec<T>>;
 
     fn poll(self: std::pin::Pin<&mut Self>, cx: &mut std::task::Context<'_>) -> std::task::Poll<Self::Output> {
         self.get_mut().0.as_mut().poll(cx)
@@ -0,0 +1,318 @@
+use crate::traits::{Field, FieldSet, Model, Numeric, Temporal};
+use bson::{doc, Bson, Document};
+
+/// DateUnit
+///
+/// Represents the unit of date truncation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DateUnit {
+    Year,
+    Quarter,
+    Month,
+    Week,
+    Day,
+    Hour,
+    Minute,
+    Second,
+}
+
+impl DateUnit {
+    /// Get the unit as mongo unit
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DateUnit::Year => "year",
+            DateUnit::Quarter => "quarter",
+            DateUnit::Month => "month",
+            DateUnit::Week => "week",
+            DateUnit::Day => "day",
+            DateUnit::Hour => "hour",
+            DateUnit::Minute => "minute",
+            DateUnit::Second => "second",
+        }
+    }
+}
+
+/// DateTrunc
+///
+/// Represents a date field truncated to a certain unit
+pub struct DateTrunc<F: Field> {
+    unit: DateUnit,
+    timezone: Option<String>,
+    _field: std::marker::PhantomData<F>,
+}
+
+impl<F> DateTrunc<F>
+where
+    F: Field,
+    F::Type: Temporal,
+{
+    /// Create a new date truncation of the field
+    pub fn new(_field: F, unit: DateUnit) -> Self {
+        Self {
+            unit,
+            timezone: None,
+            _field: std::marker::PhantomData,
+        }
+    }
+
+    /// Set the timezone used for truncation
+    pub fn timezone<S: Into<String>>(mut self, timezone: S) -> Self {
+        self.timezone = Some(timezone.into());
+        self
+    }
+
+    /// Get the `$dateTrunc` expression
+    pub fn expr(&self) -> Bson {
+        let mut trunc = doc! {
+            "date": format!("${}", F::NAME),
+            "unit": self.unit.as_str(),
+        };
+        if let Some(timezone) = &self.timezone {
+            trunc.insert("timezone", timezone);
+        }
+
+        Bson::Document(doc! {"$dateTrunc": trunc})
+    }
+}
+
+/// Group
+///
+/// Represents the `$group` stage of a model
+pub struct Group<M: Model> {
+    id: Bson,
+    /// Name of a single grouping key, kept when it moves into a compound key
+    id_name: Option<String>,
+    accumulators: Document,
+    __marker: std::marker::PhantomData<M>,
+}
+
+impl<M: Model> Group<M> {
+    /// Create a group of all documents
+    pub fn all() -> Self {
+        Self {
+            id: Bson::Null,
+            id_name: None,
+            accumulators: doc! {},
+            __marker: std::marker::PhantomData,
+        }
+    }
+
+    /// Create a group by the given fields
+    ///
+    /// Multiple fields produce a compound key named after the fields
+    pub fn by<K: FieldSet<M>>(fields: K) -> Self {
+        let names = fields.names();
+        let (id, id_name) = if names.len() == 1 {
+            (
+                Bson::String(format!("${}", names[0])),
+                Some(names[0].to_string()),
+            )
+        } else {
+            let key = names
+                .into_iter()
+                .map(|name| (name.to_string(), Bson::String(format!("${}", name))))
+                .collect::<Document>();
+            (Bson::Document(key), None)
+        };
+
+        Self {
+            id,
+            id_name,
+            ..Self::all()
+        }
+    }
+
+    /// Create a group by the truncated date
+    pub fn by_date<F>(trunc: DateTrunc<F>) -> Self
+    where
+        F: Field<Model = M>,
+        F::Type: Temporal,
+    {
+        Self {
+            id: trunc.expr(),
+            id_name: Some(F::NAME.to_string()),
+            ..Self::all()
+        }
+    }
+
+    /// Add named key into the compound group key
+    ///
+    /// A single grouping key is kept in the compound key under its field name
+    fn push_key(mut self, name: &str, key: Bson) -> Self {
+        let id = std::mem::replace(&mut self.id, Bson::Null);
+        let mut id = match (id, self.id_name.take()) {
+            (id, Some(id_name)) => doc! {id_name: id},
+            (Bson::Document(id), None) => id,
+            (_, None) => doc! {},
+        };
+        assert!(!id.contains_key(name), "duplicate group key `{}`", name);
+        id.insert(name, key);
+        self.id = Bson::Document(id);
+        self
+    }
+
+    /// Add named field into the compound group key
+    ///
+    /// Panics when the group key already has a key of the same name
+    pub fn key<F: Field<Model = M>>(self, name: &str, _field: F) -> Self {
+        let key = Bson::String(format!("${}", F::NAME));
+        self.push_key(name, key)
+    }
+
+    /// Add named truncated date into the compound group key
+    ///
+    /// Panics when the group key already has a key of the same name
+    pub fn key_date<F>(self, name: &str, trunc: DateTrunc<F>) -> Self
+    where
+        F: Field<Model = M>,
+        F::Type: Temporal,
+    {
+        self.push_key(name, trunc.expr())
+    }
+
+    fn accumulate<F: Field<Model = M>>(mut self, name: &str, op: &str) -> Self {
+        let path = format!("${}", F::NAME);
+        self.accumulators.insert(name, doc! {op: path});
+        self
+    }
+
+    /// Add `$sum` accumulator of the field
+    pub fn sum<F: Field<Model = M>>(self, name: &str, _field: F) -> Self
+    where
+        F::Type: Numeric,
+    {
+        self.accumulate::<F>(name, "$sum")
+    }
+
+    /// Add `$avg` accumulator of the field
+    pub fn avg<F: Field<Model = M>>(self, name: &str, _field: F) -> Self
+    where
+        F::Type: Numeric,
+    {
+        self.accumulate::<F>(name, "$avg")
+    }
+
+    /// Add `$min` accumulator of the field
+    pub fn min<F: Field<Model = M>>(self, name: &str, _field: F) -> Self {
+        self.accumulate::<F>(name, "$min")
+    }
+
+    /// Add `$max` accumulator of the field
+    pub fn max<F: Field<Model = M>>(self, name: &str, _field: F) -> Self {
+        self.accumulate::<F>(name, "$max")
+    }
+
+    /// Add `$first` accumulator of the field
+    pub fn first<F: Field<Model = M>>(self, name: &str, _field: F) -> Self {
+        self.accumulate::<F>(name, "$first")
+    }
+
+    /// Add `$last` accumulator of the field
+    pub fn last<F: Field<Model = M>>(self, name: &str, _field: F) -> Self {
+        self.accumulate::<F>(name, "$last")
+    }
+
+    /// Add `$push` accumulator of the field
+    pub fn push<F: Field<Model = M>>(self, name: &str, _field: F) -> Self {
+        self.accumulate::<F>(name, "$push")
+    }
+
+    /// Add `$addToSet` accumulator of the field
+    pub fn add_to_set<F: Field<Model = M>>(self, name: &str, _field: F) -> Self {
+        self.accumulate::<F>(name, "$addToSet")
+    }
+
+    /// Add accumulator counting the documents of the group
+    pub fn count(mut self, name: &str) -> Self {
+        self.accumulators.insert(name, doc! {"$sum": 1});
+        self
+    }
+}
+
+impl<M: Model> From<Group<M>> for Document {
+    fn from(value: Group<M>) -> Self {
+        let mut group = doc! {"_id": value.id};
+        group.extend(value.accumulators);
+        group
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{Age, LastSeen, Name, Scores, User};
+
+    #[test]
+    fn group_by_single_field_with_accumulators() {
+        let group = Group::<User>::by(Name)
+            .sum("total", Age)
+            .avg("average", Age)
+            .push("scores", Scores)
+            .count("count");
+        assert_eq!(
+            Document::from(group),
+            doc! {
+                "_id": "$name",
+                "total": {"$sum": "$age"},
+                "average": {"$avg": "$age"},
+                "scores": {"$push": "$scores"},
+                "count": {"$sum": 1},
+            }
+        );
+    }
+
+    #[test]
+    fn group_by_many_fields() {
+        let group = Group::<User>::by((Name, Age)).max("oldest", Age);
+        assert_eq!(
+            Document::from(group),
+            doc! {"_id": {"name": "$name", "age": "$age"}, "oldest": {"$max": "$age"}}
+        );
+        assert_eq!(
+            Document::from(Group::<User>::all()),
+            doc! {"_id": Bson::Null}
+        );
+    }
+
+    #[test]
+    fn keys_extend_the_single_grouping_key() {
+        let group = Group::<User>::by(Name).key("age", Age);
+        assert_eq!(
+            Document::from(group),
+            doc! {"_id": {"name": "$name", "age": "$age"}}
+        );
+
+        let day = DateTrunc::new(LastSeen, DateUnit::Day).timezone("Asia/Jakarta");
+        let group = Group::<User>::by_date(day).key("name", Name);
+        assert_eq!(
+            Document::from(group),
+            doc! {"_id": {
+                "last_seen": {"$dateTrunc": {
+                    "date": "$last_seen",
+                    "unit": "day",
+                    "timezone": "Asia/Jakarta",
+                }},
+                "name": "$name",
+            }}
+        );
+
+        let month = DateTrunc::new(LastSeen, DateUnit::Month);
+        let group = Group::<User>::all().key_date("month", month);
+        assert_eq!(
+            Document::from(group),
+            doc! {"_id": {"month": {"$dateTrunc": {"date": "$last_seen", "unit": "month"}}}}
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "duplicate group key `name`")]
+    fn duplicate_key_of_the_single_grouping_key() {
+        let _ = Group::<User>::by(Name).key("name", Age);
+    }
+
+    #[test]
+    #[should_panic(expected = "duplicate group key `age`")]
+    fn duplicate_key_of_the_compound_key() {
+        let _ = Group::<User>::all().key("age", Age).key("age", Age);
+    }
+}
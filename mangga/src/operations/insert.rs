@@ -1,10 +1,12 @@
-use crate::{db::get_database, traits::Model, types::BoxFut, Result};
-use mongodb::options::{
-    InsertManyOptions,
-    InsertManyOptionsBuilder,
-    InsertOneOptions,
-    InsertOneOptionsBuilder,
+use crate::{
+    db::model_database,
+    traits::Model,
+    types::{BoxFut, InsertManyResult, InsertOneResult},
+    Result,
+    Transaction,
 };
+use bson::Bson;
+use mongodb::options::{InsertManyOptions, InsertOneOptions};
 use serde::Serialize;
 use std::{
     future::{Future, IntoFuture},
@@ -17,6 +19,8 @@ use std::{
 pub struct InsertOne<'a, M: Model> {
     opts: Option<InsertOneOptions>,
     data: &'a M,
+    session: Option<Transaction>,
+    db_name: Option<String>,
 }
 
 impl<'a, M: Model> InsertOne<'a, M> {
@@ -25,15 +29,39 @@ impl<'a, M: Model> InsertOne<'a, M> {
         Self {
             opts: None,
             data,
+            session: None,
+            db_name: None,
         }
     }
 
     /// Set insert one options
+    ///
+    /// Gives access to the driver options which are not covered by the builder methods
     pub fn opts<F>(mut self, f: F) -> Self
     where
-        F: FnOnce(InsertOneOptionsBuilder<((), (), ())>) -> InsertOneOptions,
+        F: FnOnce(&mut InsertOneOptions),
     {
-        self.opts = Some(f(InsertOneOptions::builder()));
+        f(self.opts.get_or_insert_with(Default::default));
+        self
+    }
+
+    /// Attach comment to the operation
+    pub fn comment<C: Into<Bson>>(mut self, comment: C) -> Self {
+        self.opts.get_or_insert_with(Default::default).comment = Some(comment.into());
+        self
+    }
+
+    /// Run the operation in the transaction
+    pub fn session(mut self, tx: &mut Transaction) -> Self {
+        self.session = Some(tx.clone());
+        self
+    }
+
+    /// Run the operation in the database chosen at runtime
+    ///
+    /// The database is registered on first use and the model is set up in it
+    pub fn in_db<N: Into<String>>(mut self, name: N) -> Self {
+        self.db_name = Some(name.into());
         self
     }
 }
@@ -43,17 +71,23 @@ where
     M: Serialize,
 {
     type IntoFuture = InsertOneFuture;
-    type Output = Result<()>;
+    type Output = Result<InsertOneResult>;
 
     fn into_future(self) -> Self::IntoFuture {
         let data = self.data.clone();
         let opts = self.opts;
+        let session = self.session;
+        let db_name = self.db_name;
         let future = Box::pin(async move {
-            let db = get_database(M::DB_NAME)?;
+            let db = model_database::<M>(db_name).await?;
             let col = db.collection(M::MODEL_NAME);
-            col.insert_one(data).with_options(opts).await?;
+            let action = col.insert_one(data).with_options(opts);
+            let res = match session {
+                Some(tx) => action.session(&mut *tx.lock().await).await?,
+                None => action.await?,
+            };
 
-            Ok(())
+            Ok(res.into())
         });
 
         InsertOneFuture(future)
@@ -63,10 +97,10 @@ where
 /// InsertOneFuture
 ///
 /// Represents the executor of the insert one operation
-pub struct InsertOneFuture(BoxFut<()>);
+pub struct InsertOneFuture(BoxFut<InsertOneResult>);
 
 impl Future for InsertOneFuture {
-    type Output = Result<()>;
+    type Output = Result<InsertOneResult>;
 
     fn poll(
         self: Pin<&mut Self>,
@@ -82,6 +116,8 @@ impl Future for InsertOneFuture {
 pub struct InsertMany<M: Model> {
     data: Vec<M>,
     opts: Option<InsertManyOptions>,
+    session: Option<Transaction>,
+    db_name: Option<String>,
 }
 
 impl<M: Model> InsertMany<M> {
@@ -90,15 +126,39 @@ impl<M: Model> InsertMany<M> {
         Self {
             data,
             opts: None,
+            session: None,
+            db_name: None,
         }
     }
 
     /// Set insert many options
+    ///
+    /// Gives access to the driver options which are not covered by the builder methods
     pub fn opts<F>(mut self, f: F) -> Self
     where
-        F: FnOnce(InsertManyOptionsBuilder<((), (), (), ())>) -> InsertManyOptions,
+        F: FnOnce(&mut InsertManyOptions),
     {
-        self.opts = Some(f(InsertManyOptions::builder()));
+        f(self.opts.get_or_insert_with(Default::default));
+        self
+    }
+
+    /// Attach comment to the operation
+    pub fn comment<C: Into<Bson>>(mut self, comment: C) -> Self {
+        self.opts.get_or_insert_with(Default::default).comment = Some(comment.into());
+        self
+    }
+
+    /// Run the operation in the transaction
+    pub fn session(mut self, tx: &mut Transaction) -> Self {
+        self.session = Some(tx.clone());
+        self
+    }
+
+    /// Run the operation in the database chosen at runtime
+    ///
+    /// The database is registered on first use and the model is set up in it
+    pub fn in_db<N: Into<String>>(mut self, name: N) -> Self {
+        self.db_name = Some(name.into());
         self
     }
 }
@@ -108,17 +168,23 @@ where
     M: Serialize,
 {
     type IntoFuture = InsertManyFuture;
-    type Output = Result<()>;
+    type Output = Result<InsertManyResult>;
 
     fn into_future(self) -> Self::IntoFuture {
         let data = self.data;
         let opts = self.opts;
+        let session = self.session;
+        let db_name = self.db_name;
         let future = Box::pin(async move {
-            let db = get_database(M::DB_NAME)?;
+            let db = model_database::<M>(db_name).await?;
             let col = db.collection(M::MODEL_NAME);
-            col.insert_many(data).with_options(opts).await?;
+            let action = col.insert_many(data).with_options(opts);
+            let res = match session {
+                Some(tx) => action.session(&mut *tx.lock().await).await?,
+                None => action.await?,
+            };
 
-            Ok(())
+            Ok(res.into())
         });
 
         InsertManyFuture(future)
@@ -128,10 +194,10 @@ where
 /// InsertManyFuture
 ///
 /// Represents the executor of the insert many operation
-pub struct InsertManyFuture(BoxFut<()>);
+pub struct InsertManyFuture(BoxFut<InsertManyResult>);
 
 impl Future for InsertManyFuture {
-    type Output = Result<()>;
+    type Output = Result<InsertManyResult>;
 
     fn poll(
         self: Pin<&mut Self>,
@@ -0,0 +1,354 @@
+use super::FindMany;
+use crate::{db::model_database, traits::Model, types::BoxFut, Error, Result};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use bson::{doc, Bson, Document};
+use futures::TryStreamExt;
+use serde::{Deserialize, Serialize};
+use std::future::{Future, IntoFuture};
+
+/// KeysetPage
+///
+/// Represents a page of documents with opaque cursors of its boundaries
+#[derive(Debug, Clone, Serialize)]
+pub struct KeysetPage<T> {
+    /// Documents of the page
+    pub items: Vec<T>,
+    /// Cursor to fetch the next page
+    pub next: Option<String>,
+    /// Cursor to fetch the previous page
+    pub prev: Option<String>,
+}
+
+/// Direction of the keyset cursor
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Direction {
+    After,
+    Before,
+}
+
+/// Keyset
+///
+/// Represents the keyset pagination operation
+///
+/// The range filter is built from the sort keys with `_id` as tie-breaker
+pub struct Keyset<M: Model, T = M> {
+    find: FindMany<M, T>,
+    cursor: Option<(Direction, String)>,
+}
+
+impl<M: Model, T> Keyset<M, T> {
+    /// Create a new keyset pagination starting from the first page
+    pub fn new(find: FindMany<M, T>) -> Self {
+        Self { find, cursor: None }
+    }
+
+    /// Fetch documents after the cursor
+    pub fn after<C: Into<String>>(mut self, cursor: C) -> Self {
+        self.cursor = Some((Direction::After, cursor.into()));
+        self
+    }
+
+    /// Fetch documents before the cursor
+    pub fn before<C: Into<String>>(mut self, cursor: C) -> Self {
+        self.cursor = Some((Direction::Before, cursor.into()));
+        self
+    }
+}
+
+/// Get the sort keys with `_id` as tie-breaker
+fn sort_keys(sort: Option<&Document>) -> Result<Vec<(String, i32)>> {
+    let mut keys = vec![];
+    for (name, value) in sort.into_iter().flatten() {
+        let dir = match value {
+            Bson::Int32(1) | Bson::Int64(1) => 1,
+            Bson::Int32(-1) | Bson::Int64(-1) => -1,
+            Bson::Double(d) if *d == 1.0 => 1,
+            Bson::Double(d) if *d == -1.0 => -1,
+            _ => {
+                return Err(Error::Cursor(format!(
+                    "sort key `{}` can not be used for keyset pagination",
+                    name
+                )))
+            }
+        };
+        keys.push((name.clone(), dir));
+    }
+
+    if !keys.iter().any(|(name, _)| name == "_id") {
+        let dir = keys.last().map(|(_, dir)| *dir).unwrap_or(1);
+        keys.push(("_id".to_string(), dir));
+    }
+
+    Ok(keys)
+}
+
+/// Get value of the dotted path
+fn lookup(doc: &Document, path: &str) -> Bson {
+    let mut parts = path.split('.');
+    let mut current = parts.next().and_then(|p| doc.get(p));
+    for part in parts {
+        current = match current {
+            Some(Bson::Document(d)) => d.get(part),
+            _ => None,
+        };
+    }
+    current.cloned().unwrap_or(Bson::Null)
+}
+
+/// Encode the sort values of the document as cursor
+fn encode(keys: &[(String, i32)], doc: &Document) -> Result<String> {
+    let values = keys
+        .iter()
+        .map(|(name, _)| (name.clone(), lookup(doc, name)))
+        .collect::<Document>();
+    let bytes = bson::to_vec(&values).map_err(|e| Error::Cursor(e.to_string()))?;
+    Ok(URL_SAFE_NO_PAD.encode(bytes))
+}
+
+/// Decode the cursor into sort values
+fn decode(keys: &[(String, i32)], cursor: &str) -> Result<Document> {
+    let bytes = URL_SAFE_NO_PAD
+        .decode(cursor)
+        .map_err(|e| Error::Cursor(e.to_string()))?;
+    let values =
+        Document::from_reader(bytes.as_slice()).map_err(|e| Error::Cursor(e.to_string()))?;
+    if keys.len() != values.len() || keys.iter().any(|(name, _)| !values.contains_key(name)) {
+        return Err(Error::Cursor(
+            "cursor does not match the sort keys".to_string(),
+        ));
+    }
+    Ok(values)
+}
+
+/// Build range filter of the documents after the values in the sort order
+///
+/// The values are keyed by the full sort key, dotted paths included. Null and missing
+/// values sort before any other value, so they are matched explicitly
+fn range_filter(keys: &[(String, i32)], values: &Document, reverse: bool) -> Document {
+    let mut clauses = vec![];
+    for (i, (name, dir)) in keys.iter().enumerate() {
+        let mut clause = Document::new();
+        for (prev, _) in &keys[..i] {
+            clause.insert(prev, doc! {"$eq": value(values, prev)});
+        }
+        let value = value(values, name);
+        let ascending = (*dir == 1) != reverse;
+        match (value, ascending) {
+            // every value but null comes after null
+            (Bson::Null, true) => {
+                clause.insert(name, doc! {"$ne": Bson::Null});
+            }
+            // nothing comes before null
+            (Bson::Null, false) => continue,
+            (value, true) => {
+                clause.insert(name, doc! {"$gt": value});
+            }
+            // `_id` is never null
+            (value, false) if name == "_id" => {
+                clause.insert(name, doc! {"$lt": value});
+            }
+            // null and missing values come after any other value
+            (value, false) => {
+                clause.insert(
+                    "$or",
+                    vec![doc! {name: {"$lt": value}}, doc! {name: Bson::Null}],
+                );
+            }
+        }
+        clauses.push(clause);
+    }
+
+    if clauses.len() == 1 {
+        clauses.remove(0)
+    } else {
+        doc! {"$or": clauses}
+    }
+}
+
+/// Get the cursor value of the sort key
+fn value(values: &Document, name: &str) -> Bson {
+    values.get(name).cloned().unwrap_or(Bson::Null)
+}
+
+impl<M: Model, T> IntoFuture for Keyset<M, T>
+where
+    T: for<'de> Deserialize<'de> + Send + Sync + 'static,
+{
+    type IntoFuture = KeysetFuture<T>;
+    type Output = Result<KeysetPage<T>>;
+
+    fn into_future(self) -> Self::IntoFuture {
+        let Self { find, cursor } = self;
+        KeysetFuture(Box::pin(async move {
+            let mut opts = find.opts.unwrap_or_default();
+            let keys = sort_keys(opts.sort.as_ref())?;
+            let (direction, values) = match cursor {
+                Some((direction, cursor)) => (direction, Some(decode(&keys, &cursor)?)),
+                None => (Direction::After, None),
+            };
+            let reverse = direction == Direction::Before;
+
+            let mut filter = find.filter;
+            if let Some(values) = &values {
+                let range = range_filter(&keys, values, reverse);
+                filter = if filter.is_empty() {
+                    range
+                } else {
+                    doc! {"$and": [filter, range]}
+                };
+            }
+
+            // make sure the sort values are returned by the projection
+            if let Some(projection) = opts.projection.as_mut() {
+                let inclusion = projection.iter().any(|(name, value)| {
+                    name != "_id"
+                        && !matches!(
+                            value,
+                            Bson::Int32(0) | Bson::Int64(0) | Bson::Boolean(false)
+                        )
+                });
+                for (name, _) in &keys {
+                    if inclusion {
+                        projection.insert(name, 1);
+                    } else {
+                        projection.remove(name);
+                    }
+                }
+            }
+
+            let limit = opts.limit.map(i64::abs);
+            opts.sort = Some(
+                keys.iter()
+                    .map(|(name, dir)| {
+                        (name.clone(), Bson::Int32(if reverse { -dir } else { *dir }))
+                    })
+                    .collect(),
+            );
+            opts.skip = None;
+            opts.limit = limit.map(|l| l + 1);
+
+            let db = model_database::<M>(find.db_name).await?;
+            let col = db.collection::<Document>(M::MODEL_NAME);
+            let action = col.find(filter).with_options(opts);
+            let mut docs = match find.session {
+                Some(tx) => {
+                    let mut session = tx.lock().await;
+                    let mut cursor = action.session(&mut *session).await?;
+                    cursor.stream(&mut session).try_collect::<Vec<_>>().await?
+                }
+                None => action.await?.try_collect::<Vec<_>>().await?,
+            };
+
+            let has_more = limit.is_some_and(|l| docs.len() as i64 > l);
+            if let Some(limit) = limit {
+                docs.truncate(limit as usize);
+            }
+            if reverse {
+                docs.reverse();
+            }
+
+            let first = docs.first().map(|d| encode(&keys, d)).transpose()?;
+            let last = docs.last().map(|d| encode(&keys, d)).transpose()?;
+            let (next, prev) = if reverse {
+                (last, if has_more { first } else { None })
+            } else {
+                (if has_more { last } else { None }, values.and(first))
+            };
+
+            let items = docs
+                .into_iter()
+                .map(bson::from_document::<T>)
+                .collect::<std::result::Result<Vec<_>, _>>()
+                .map_err(mongodb::error::Error::from)?;
+
+            Ok(KeysetPage { items, next, prev })
+        }))
+    }
+}
+
+/// KeysetFuture
+///
+/// Represents the executor of the keyset pagination operation
+pub struct KeysetFuture<T>(BoxFut<KeysetPage<T>>);
+
+impl<T> Future for KeysetFuture<T> {
+    type Output = Result<KeysetPage<T>>;
+
+    fn poll(
+        self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Self::Output> {
+        self.get_mut().0.as_mut().poll(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keys() -> Vec<(String, i32)> {
+        vec![("address.city".to_string(), 1), ("_id".to_string(), 1)]
+    }
+
+    #[test]
+    fn cursor_round_trip_keeps_dotted_keys() {
+        let doc = doc! {"_id": 7, "name": "a", "address": {"city": "Jakarta"}};
+        let cursor = encode(&keys(), &doc).unwrap();
+        let values = decode(&keys(), &cursor).unwrap();
+        assert_eq!(values, doc! {"address.city": "Jakarta", "_id": 7});
+    }
+
+    #[test]
+    fn cursor_of_other_sort_keys_is_rejected() {
+        let cursor = encode(&keys(), &doc! {"_id": 7}).unwrap();
+        let other = vec![("name".to_string(), 1), ("_id".to_string(), 1)];
+        assert!(decode(&other, &cursor).is_err());
+        assert!(decode(&keys(), "not a cursor").is_err());
+    }
+
+    #[test]
+    fn sort_keys_end_with_id() {
+        let keys = sort_keys(Some(&doc! {"age": -1})).unwrap();
+        assert_eq!(keys, vec![("age".to_string(), -1), ("_id".to_string(), -1)]);
+        assert_eq!(sort_keys(None).unwrap(), vec![("_id".to_string(), 1)]);
+        assert!(sort_keys(Some(&doc! {"name": {"$meta": "textScore"}})).is_err());
+    }
+
+    #[test]
+    fn range_filter_reads_dotted_keys() {
+        let doc = doc! {"_id": 7, "address": {"city": "Jakarta"}};
+        let values = decode(&keys(), &encode(&keys(), &doc).unwrap()).unwrap();
+        assert_eq!(
+            range_filter(&keys(), &values, false),
+            doc! {"$or": [
+                {"address.city": {"$gt": "Jakarta"}},
+                {"address.city": {"$eq": "Jakarta"}, "_id": {"$gt": 7}},
+            ]}
+        );
+    }
+
+    #[test]
+    fn range_filter_matches_null_values() {
+        let values = doc! {"address.city": Bson::Null, "_id": 7};
+        assert_eq!(
+            range_filter(&keys(), &values, false),
+            doc! {"$or": [
+                {"address.city": {"$ne": Bson::Null}},
+                {"address.city": {"$eq": Bson::Null}, "_id": {"$gt": 7}},
+            ]}
+        );
+        assert_eq!(
+            range_filter(&keys(), &values, true),
+            doc! {"address.city": {"$eq": Bson::Null}, "_id": {"$lt": 7}}
+        );
+
+        let values = doc! {"address.city": "Jakarta", "_id": 7};
+        assert_eq!(
+            range_filter(&keys(), &values, true),
+            doc! {"$or": [
+                {"$or": [{"address.city": {"$lt": "Jakarta"}}, {"address.city": Bson::Null}]},
+                {"address.city": {"$eq": "Jakarta"}, "_id": {"$lt": 7}},
+            ]}
+        );
+    }
+}
@@ -1,13 +1,27 @@
+mod aggregate;
 mod filter;
 mod find;
+mod group;
 mod insert;
+mod keyset;
+mod paginate;
 mod delete;
 mod count;
+mod sort;
+mod stream;
 mod update;
+mod watch;
 
+pub use aggregate::*;
 pub use insert::*;
+pub use keyset::*;
+pub use paginate::*;
 pub use find::*;
 pub use filter::*;
+pub use group::*;
 pub use delete::*;
 pub use count::*;
-pub use update::*;
\ No newline at end of file
+pub use sort::*;
+pub use stream::*;
+pub use update::*;
+pub use watch::*;
@@ -0,0 +1,272 @@
+use super::{Count, FindMany};
+use crate::{db::model_database, traits::Model, types::BoxFut, Error, Result};
+use bson::{doc, Document};
+use futures::TryStreamExt;
+use mongodb::options::AggregateOptions;
+use serde::{Deserialize, Serialize};
+use std::future::{Future, IntoFuture};
+
+/// Page
+///
+/// Represents a page of documents with its metadata
+#[derive(Debug, Clone, Serialize)]
+pub struct Page<T> {
+    /// Documents of the page
+    pub items: Vec<T>,
+    /// Total number of documents matched by the filter
+    pub total: usize,
+    /// Current page, starts from 1
+    pub page: usize,
+    /// Number of documents per page
+    pub per_page: usize,
+    /// Total number of pages
+    pub total_pages: usize,
+    /// Whether there is a next page
+    pub has_next: bool,
+    /// Whether there is a previous page
+    pub has_prev: bool,
+}
+
+impl<T> Page<T> {
+    /// Create a new page
+    pub fn new(items: Vec<T>, total: usize, page: usize, per_page: usize) -> Self {
+        let total_pages = total.div_ceil(per_page);
+        Self {
+            items,
+            total,
+            page,
+            per_page,
+            total_pages,
+            has_next: page < total_pages,
+            has_prev: page > 1,
+        }
+    }
+}
+
+/// Paginate
+///
+/// Represents the paginate operation
+pub struct Paginate<M: Model, T = M> {
+    find: FindMany<M, T>,
+    page: usize,
+    per_page: usize,
+    facet: bool,
+}
+
+impl<M: Model, T> Paginate<M, T> {
+    /// Create a new paginate operation
+    ///
+    /// Page starts from 1, zero values are treated as 1.
+    /// Skip and limit come from the page, setting them on the find is an error
+    pub fn new(find: FindMany<M, T>, page: usize, per_page: usize) -> Self {
+        Self {
+            find,
+            page: page.max(1),
+            per_page: per_page.max(1),
+            facet: false,
+        }
+    }
+
+    /// Run the page query and the count in a single `$facet` aggregation
+    pub fn facet(mut self) -> Self {
+        self.facet = true;
+        self
+    }
+}
+
+/// Facet
+///
+/// Represents the result of the `$facet` pagination
+#[derive(Deserialize)]
+struct Facet<T> {
+    items: Vec<T>,
+    total: Vec<FacetTotal>,
+}
+
+#[derive(Deserialize)]
+struct FacetTotal {
+    count: i64,
+}
+
+/// Set skip and limit of the find to the page
+fn window<M: Model, T>(
+    find: FindMany<M, T>,
+    page: usize,
+    per_page: usize,
+) -> Result<FindMany<M, T>> {
+    if find
+        .opts
+        .as_ref()
+        .is_some_and(|opts| opts.skip.is_some() || opts.limit.is_some())
+    {
+        return Err(Error::Page(
+            "skip and limit are set by the page".to_string(),
+        ));
+    }
+
+    // the server reads skip and limit as 64-bit signed integers
+    let skip = (page - 1)
+        .checked_mul(per_page)
+        .and_then(|skip| i64::try_from(skip).ok());
+    let limit = i64::try_from(per_page).ok();
+    match (skip, limit) {
+        (Some(skip), Some(limit)) => Ok(find.skip(skip as u64).limit(limit)),
+        _ => Err(Error::Page(format!(
+            "page {} of {} documents is out of range",
+            page, per_page
+        ))),
+    }
+}
+
+impl<M: Model, T> IntoFuture for Paginate<M, T>
+where
+    T: for<'de> Deserialize<'de> + Send + Sync + 'static,
+{
+    type IntoFuture = PaginateFuture<T>;
+    type Output = Result<Page<T>>;
+
+    fn into_future(self) -> Self::IntoFuture {
+        let Self {
+            find,
+            page,
+            per_page,
+            facet,
+        } = self;
+
+        let find = match window(find, page, per_page) {
+            Ok(find) => find,
+            Err(e) => return PaginateFuture(Box::pin(async move { Err(e) })),
+        };
+
+        if !facet {
+            let mut count = Count::<M>::new(find.filter.clone());
+            if let Some(collation) = find.opts.as_ref().and_then(|o| o.collation.clone()) {
+                count = count.collation(collation);
+            }
+            if let Some(name) = find.db_name.clone() {
+                count = count.in_db(name);
+            }
+            if let Some(mut tx) = find.session.clone() {
+                count = count.session(&mut tx);
+            }
+
+            return PaginateFuture(Box::pin(async move {
+                let (items, total) = futures::try_join!(find.into_future(), count.into_future())?;
+                Ok(Page::new(items, total, page, per_page))
+            }));
+        }
+
+        let opts = find.opts.unwrap_or_default();
+        let mut pipeline = vec![doc! {"$match": find.filter}];
+        if let Some(sort) = opts.sort {
+            pipeline.push(doc! {"$sort": sort});
+        }
+        let mut items = vec![
+            doc! {"$skip": opts.skip.unwrap_or_default() as i64},
+            doc! {"$limit": opts.limit.unwrap_or(per_page as i64)},
+        ];
+        if let Some(projection) = opts.projection {
+            items.push(doc! {"$project": projection});
+        }
+        pipeline.push(doc! {
+            "$facet": {
+                "items": items,
+                "total": [{"$count": "count"}],
+            }
+        });
+
+        let mut agg_opts = AggregateOptions::default();
+        agg_opts.collation = opts.collation;
+        agg_opts.hint = opts.hint;
+        agg_opts.max_time = opts.max_time;
+        agg_opts.comment = opts.comment;
+
+        let session = find.session;
+        let db_name = find.db_name;
+        PaginateFuture(Box::pin(async move {
+            let db = model_database::<M>(db_name).await?;
+            let col = db.collection::<Document>(M::MODEL_NAME);
+            let action = col
+                .aggregate(pipeline)
+                .with_options(agg_opts)
+                .with_type::<Facet<T>>();
+            let res = match session {
+                Some(tx) => {
+                    let mut session = tx.lock().await;
+                    let mut cursor = action.session(&mut *session).await?;
+                    cursor.next(&mut session).await.transpose()?
+                }
+                None => action.await?.try_next().await?,
+            };
+            let (items, total) = match res {
+                Some(facet) => {
+                    let total = facet.total.first().map(|t| t.count).unwrap_or_default();
+                    (facet.items, total as usize)
+                }
+                None => (vec![], 0),
+            };
+
+            Ok(Page::new(items, total, page, per_page))
+        }))
+    }
+}
+
+/// PaginateFuture
+///
+/// Represents the executor of the paginate operation
+pub struct PaginateFuture<T>(BoxFut<Page<T>>);
+
+impl<T> Future for PaginateFuture<T> {
+    type Output = Result<Page<T>>;
+
+    fn poll(
+        self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Self::Output> {
+        self.get_mut().0.as_mut().poll(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::User;
+
+    fn window_of(find: FindMany<User>, page: usize, per_page: usize) -> Result<(u64, i64)> {
+        let opts = window(find, page, per_page)?.opts.unwrap_or_default();
+        Ok((
+            opts.skip.unwrap_or_default(),
+            opts.limit.unwrap_or_default(),
+        ))
+    }
+
+    #[test]
+    fn window_sets_skip_and_limit_from_the_page() {
+        assert_eq!(window_of(FindMany::new(doc! {}), 1, 20).unwrap(), (0, 20));
+        assert_eq!(window_of(FindMany::new(doc! {}), 3, 20).unwrap(), (40, 20));
+    }
+
+    #[test]
+    fn new_clamps_zero_page_and_per_page() {
+        let paginate = Paginate::new(FindMany::<User>::new(doc! {}), 0, 0);
+        assert_eq!((paginate.page, paginate.per_page), (1, 1));
+    }
+
+    #[test]
+    fn window_rejects_out_of_range_pages() {
+        let res = window_of(FindMany::new(doc! {}), usize::MAX, 2);
+        assert!(matches!(res, Err(Error::Page(_))));
+
+        let res = window_of(FindMany::new(doc! {}), 1, usize::MAX);
+        assert!(matches!(res, Err(Error::Page(_))));
+    }
+
+    #[test]
+    fn window_rejects_skip_or_limit_set_by_the_caller() {
+        let res = window_of(FindMany::new(doc! {}).skip(5), 1, 20);
+        assert!(matches!(res, Err(Error::Page(_))));
+
+        let res = window_of(FindMany::new(doc! {}).limit(5), 1, 20);
+        assert!(matches!(res, Err(Error::Page(_))));
+    }
+}
@@ -0,0 +1,99 @@
+use crate::traits::IntoSort;
+use bson::{doc, Document};
+
+/// Sort
+///
+/// Represents special sort keys
+pub struct Sort;
+
+impl Sort {
+    /// Sort by text search score stored in the given name
+    pub fn text_score(name: &str) -> Document {
+        doc! {name: {"$meta": "textScore"}}
+    }
+
+    /// Sort by natural order
+    pub fn natural_asc() -> Document {
+        doc! {"$natural": 1}
+    }
+
+    /// Sort by reverse natural order
+    pub fn natural_desc() -> Document {
+        doc! {"$natural": -1}
+    }
+}
+
+impl IntoSort for Document {
+    fn into_sort(self) -> Document {
+        self
+    }
+}
+
+impl<T: IntoSort> IntoSort for Vec<T> {
+    fn into_sort(self) -> Document {
+        let mut sort = Document::new();
+        for key in self {
+            sort.extend(key.into_sort());
+        }
+        sort
+    }
+}
+
+macro_rules! impl_into_sort {
+    ($($t:ident),+) => {
+        impl<$($t: IntoSort),+> IntoSort for ($($t,)+) {
+            #[allow(non_snake_case)]
+            fn into_sort(self) -> Document {
+                let ($($t,)+) = self;
+                let mut sort = Document::new();
+                $(sort.extend($t.into_sort());)+
+                sort
+            }
+        }
+    };
+}
+
+impl_into_sort!(A);
+impl_into_sort!(A, B);
+impl_into_sort!(A, B, C);
+impl_into_sort!(A, B, C, D);
+impl_into_sort!(A, B, C, D, E);
+impl_into_sort!(A, B, C, D, E, F);
+impl_into_sort!(A, B, C, D, E, F, G);
+impl_into_sort!(A, B, C, D, E, F, G, H);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        testing::{Age, Name},
+        traits::SortAble,
+    };
+
+    /// Documents compare regardless of key order, the order of sort keys matters
+    fn keys(sort: Document) -> Vec<(String, bson::Bson)> {
+        sort.into_iter().collect()
+    }
+
+    #[test]
+    fn tuple_keeps_the_order_of_keys() {
+        assert_eq!(
+            keys((Age.desc(), Name.asc()).into_sort()),
+            keys(doc! {"age": -1, "name": 1})
+        );
+        assert_eq!(
+            keys((Name.asc(), Age.desc()).into_sort()),
+            keys(doc! {"name": 1, "age": -1})
+        );
+    }
+
+    #[test]
+    fn vec_keeps_the_order_of_keys() {
+        let sort = vec![Sort::text_score("score"), Age.desc(), Name.asc()].into_sort();
+        assert_eq!(
+            keys(sort),
+            keys(doc! {"score": {"$meta": "textScore"}, "age": -1, "name": 1})
+        );
+        assert_eq!(Sort::natural_desc().into_sort(), doc! {"$natural": -1});
+    }
+}
@@ -0,0 +1,39 @@
+use crate::{types::BoxStream, Error, Result, Transaction};
+use futures::{stream, Stream};
+use mongodb::SessionCursor;
+use serde::de::DeserializeOwned;
+
+/// CursorStream
+///
+/// Represents a stream of documents backed by a database cursor
+pub struct CursorStream<T>(BoxStream<T>);
+
+impl<T> CursorStream<T> {
+    /// Create a new cursor stream
+    pub(crate) fn new(stream: BoxStream<T>) -> Self {
+        Self(stream)
+    }
+
+    /// Create a new cursor stream iterating the cursor within the transaction
+    pub(crate) fn with_session(cursor: SessionCursor<T>, tx: Transaction) -> Self
+    where
+        T: DeserializeOwned + Send + Sync + 'static,
+    {
+        let stream = stream::unfold((cursor, tx), |(mut cursor, tx)| async move {
+            let next = cursor.next(&mut *tx.lock().await).await?;
+            Some((next.map_err(Error::from), (cursor, tx)))
+        });
+        Self(Box::pin(stream))
+    }
+}
+
+impl<T> Stream for CursorStream<T> {
+    type Item = Result<T>;
+
+    fn poll_next(
+        self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Option<Self::Item>> {
+        self.get_mut().0.as_mut().poll_next(cx)
+    }
+}
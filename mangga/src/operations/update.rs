@@ -1,9 +1,306 @@
-use crate::{db::get_database, traits::Model, types::BoxFut, Result};
+use super::Operator;
+use crate::{
+    db::model_database,
+    traits::{Array, Field, Model, Numeric},
+    types::{BoxFut, ModelIndex, UpdateResult},
+    Result,
+    Transaction,
+};
 use bson::{doc, Bson, Document};
-use mongodb::options::{UpdateOptions, UpdateOptionsBuilder};
+use mongodb::options::{Collation, UpdateOptions};
 use serde::Serialize;
 use std::future::{Future, IntoFuture};
 
+/// Update
+///
+/// Represents the update document of a model
+#[derive(Debug, Clone)]
+pub struct Update<M: Model> {
+    doc: Document,
+    __marker: std::marker::PhantomData<M>,
+}
+
+impl<M: Model> Update<M> {
+    /// Create a new empty update
+    pub fn new() -> Self {
+        Self {
+            doc: doc! {},
+            __marker: std::marker::PhantomData,
+        }
+    }
+
+    /// Create an update with single operator on a field
+    pub fn with<N: Into<String>, V: Into<Bson>>(op: &str, name: N, value: V) -> Self {
+        let mut update = Self::new();
+        update.push(op, name.into(), value.into());
+        update
+    }
+
+    /// Combine with another update
+    ///
+    /// Later values win when both updates touch the same field with the same operator
+    pub fn and<U: Into<Update<M>>>(mut self, other: U) -> Self {
+        for (op, fields) in other.into().doc {
+            if let Bson::Document(fields) = fields {
+                for (name, value) in fields {
+                    self.push(&op, name, value);
+                }
+            }
+        }
+        self
+    }
+
+    /// Check if the update is empty
+    pub fn is_empty(&self) -> bool {
+        self.doc.is_empty()
+    }
+
+    fn push(&mut self, op: &str, name: String, value: Bson) {
+        match self.doc.get_mut(op) {
+            Some(Bson::Document(fields)) => {
+                fields.insert(name, value);
+            }
+            _ => {
+                self.doc.insert(op, doc! {name: value});
+            }
+        }
+    }
+}
+
+impl<M: Model> Default for Update<M> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<M: Model> From<(String, Bson)> for Update<M> {
+    fn from((name, value): (String, Bson)) -> Self {
+        Self::with("$set", name, value)
+    }
+}
+
+impl<M: Model> From<Vec<(String, Bson)>> for Update<M> {
+    fn from(value: Vec<(String, Bson)>) -> Self {
+        value
+            .into_iter()
+            .fold(Self::new(), |update, set| update.and(set))
+    }
+}
+
+impl<M: Model> From<Update<M>> for Document {
+    fn from(value: Update<M>) -> Self {
+        value.doc
+    }
+}
+
+/// PushEach
+///
+/// Represents `$push` update with `$each` modifier
+pub struct PushEach<F: Field> {
+    values: Vec<Bson>,
+    slice: Option<i32>,
+    sort: Option<Bson>,
+    position: Option<i32>,
+    _field: std::marker::PhantomData<F>,
+}
+
+impl<F: Field> PushEach<F> {
+    /// Create a new push each update
+    pub fn new(values: Vec<Bson>) -> Self {
+        Self {
+            values,
+            slice: None,
+            sort: None,
+            position: None,
+            _field: std::marker::PhantomData,
+        }
+    }
+
+    /// Limit the array size after push using `$slice`
+    pub fn slice(mut self, slice: i32) -> Self {
+        self.slice = Some(slice);
+        self
+    }
+
+    /// Sort the elements ascending using `$sort`
+    pub fn sort_asc(mut self) -> Self {
+        self.sort = Some(Bson::Int32(1));
+        self
+    }
+
+    /// Sort the elements descending using `$sort`
+    pub fn sort_desc(mut self) -> Self {
+        self.sort = Some(Bson::Int32(-1));
+        self
+    }
+
+    /// Sort embedded document elements by the given sort document
+    pub fn sort_by(mut self, sort: Document) -> Self {
+        self.sort = Some(Bson::Document(sort));
+        self
+    }
+
+    /// Insert the elements at the given index using `$position`
+    pub fn position(mut self, position: i32) -> Self {
+        self.position = Some(position);
+        self
+    }
+}
+
+impl<F: Field> From<PushEach<F>> for Update<F::Model> {
+    fn from(value: PushEach<F>) -> Self {
+        let mut each = doc! {"$each": value.values};
+        if let Some(slice) = value.slice {
+            each.insert("$slice", slice);
+        }
+        if let Some(sort) = value.sort {
+            each.insert("$sort", sort);
+        }
+        if let Some(position) = value.position {
+            each.insert("$position", position);
+        }
+
+        Update::with("$push", F::NAME, each)
+    }
+}
+
+/// Positional
+///
+/// Represents a positional path of an array field
+pub struct Positional<F: Field> {
+    path: String,
+    _field: std::marker::PhantomData<F>,
+}
+
+impl<F> Positional<F>
+where
+    F: Field,
+    F::Type: Array,
+{
+    /// Create a new positional path
+    pub fn new(operator: &str) -> Self {
+        Self {
+            path: format!("{}.{}", F::NAME, operator),
+            _field: std::marker::PhantomData,
+        }
+    }
+
+    /// Set the element value
+    pub fn set<V: Into<<F::Type as Array>::Item>>(self, value: V) -> Update<F::Model>
+    where
+        <F::Type as Array>::Item: Into<Bson>,
+    {
+        Update::with("$set", self.path, value.into())
+    }
+
+    /// Increment the element value
+    pub fn inc<V: Into<<F::Type as Array>::Item>>(self, value: V) -> Update<F::Model>
+    where
+        <F::Type as Array>::Item: Numeric,
+    {
+        Update::with("$inc", self.path, value.into())
+    }
+
+    /// Remove the element value
+    pub fn unset(self) -> Update<F::Model> {
+        Update::with("$unset", self.path, "")
+    }
+}
+
+/// Elem
+///
+/// Represents an identifier of array elements used by `$[<identifier>]`
+pub struct Elem<F: Field> {
+    ident: String,
+    _field: std::marker::PhantomData<F>,
+}
+
+impl<F> Elem<F>
+where
+    F: Field,
+    F::Type: Array,
+    <F::Type as Array>::Item: Into<Bson>,
+{
+    /// Create a new element identifier
+    pub fn new<S: Into<String>>(ident: S) -> Self {
+        Self {
+            ident: ident.into(),
+            _field: std::marker::PhantomData,
+        }
+    }
+
+    /// Get positional path of the filtered elements
+    pub fn positional(&self) -> Positional<F> {
+        Positional::new(&format!("$[{}]", self.ident))
+    }
+
+    fn filter<V: Into<Bson>>(&self, op: Operator, value: V) -> ArrayFilter<F::Model> {
+        let ident = self.ident.clone();
+        let op = op.as_str();
+        ArrayFilter {
+            doc: doc! {ident: {op: value.into()}},
+            __marker: std::marker::PhantomData,
+        }
+    }
+
+    /// Create `eq` array filter
+    pub fn eq<V: Into<<F::Type as Array>::Item>>(&self, value: V) -> ArrayFilter<F::Model> {
+        self.filter(Operator::Eq, value.into())
+    }
+
+    /// Create `ne` array filter
+    pub fn ne<V: Into<<F::Type as Array>::Item>>(&self, value: V) -> ArrayFilter<F::Model> {
+        self.filter(Operator::Ne, value.into())
+    }
+
+    /// Create `lt` array filter
+    pub fn lt<V: Into<<F::Type as Array>::Item>>(&self, value: V) -> ArrayFilter<F::Model> {
+        self.filter(Operator::Lt, value.into())
+    }
+
+    /// Create `gt` array filter
+    pub fn gt<V: Into<<F::Type as Array>::Item>>(&self, value: V) -> ArrayFilter<F::Model> {
+        self.filter(Operator::Gt, value.into())
+    }
+
+    /// Create `lte` array filter
+    pub fn lte<V: Into<<F::Type as Array>::Item>>(&self, value: V) -> ArrayFilter<F::Model> {
+        self.filter(Operator::Lte, value.into())
+    }
+
+    /// Create `gte` array filter
+    pub fn gte<V: Into<<F::Type as Array>::Item>>(&self, value: V) -> ArrayFilter<F::Model> {
+        self.filter(Operator::Gte, value.into())
+    }
+
+    /// Create `in` array filter
+    pub fn is_in<T: Into<<F::Type as Array>::Item>, V: IntoIterator<Item = T>>(
+        &self,
+        value: V,
+    ) -> ArrayFilter<F::Model> {
+        let values = value
+            .into_iter()
+            .map(|v| v.into().into())
+            .collect::<Vec<Bson>>();
+        self.filter(Operator::In, values)
+    }
+}
+
+/// ArrayFilter
+///
+/// Represents a filter of `arrayFilters` update option
+#[derive(Debug, Clone)]
+pub struct ArrayFilter<M: Model> {
+    doc: Document,
+    __marker: std::marker::PhantomData<M>,
+}
+
+impl<M: Model> From<ArrayFilter<M>> for Document {
+    fn from(value: ArrayFilter<M>) -> Self {
+        value.doc
+    }
+}
+
 /// UpdateOne
 ///
 /// Represents the update one operation
@@ -11,28 +308,71 @@ pub struct UpdateOne<M: Model> {
     opts: Option<UpdateOptions>,
     filter: Document,
     update: Document,
+    session: Option<Transaction>,
+    db_name: Option<String>,
     __marker: std::marker::PhantomData<M>,
 }
 
 impl<M: Model> UpdateOne<M> {
     /// Create a new update one operation
-    pub fn new(filter: Document, update: Vec<(String, Bson)>) -> Self {
+    pub fn new(filter: Document, update: Update<M>) -> Self {
         Self {
             opts: None,
             filter,
-            update: doc! {
-                "$set": Document::from_iter(update)
-            },
+            update: update.into(),
+            session: None,
+            db_name: None,
             __marker: std::marker::PhantomData,
         }
     }
 
     /// Set update one options
+    ///
+    /// Gives access to the driver options which are not covered by the builder methods
     pub fn opts<F>(mut self, f: F) -> Self
     where
-        F: FnOnce(UpdateOptionsBuilder<((), (), (), (), (), (), (), ())>) -> UpdateOptions,
+        F: FnOnce(&mut UpdateOptions),
     {
-        self.opts = Some(f(UpdateOptions::builder()));
+        f(self.opts.get_or_insert_with(Default::default));
+        self
+    }
+
+    /// Use the index of the model
+    pub fn hint(mut self, index: ModelIndex<M>) -> Self {
+        self.opts.get_or_insert_with(Default::default).hint = Some(index.into());
+        self
+    }
+
+    /// Set the collation
+    pub fn collation(mut self, collation: Collation) -> Self {
+        self.opts.get_or_insert_with(Default::default).collation = Some(collation);
+        self
+    }
+
+    /// Attach comment to the operation
+    pub fn comment<C: Into<Bson>>(mut self, comment: C) -> Self {
+        self.opts.get_or_insert_with(Default::default).comment = Some(comment.into());
+        self
+    }
+
+    /// Run the operation in the transaction
+    pub fn session(mut self, tx: &mut Transaction) -> Self {
+        self.session = Some(tx.clone());
+        self
+    }
+
+    /// Run the operation in the database chosen at runtime
+    ///
+    /// The database is registered on first use and the model is set up in it
+    pub fn in_db<N: Into<String>>(mut self, name: N) -> Self {
+        self.db_name = Some(name.into());
+        self
+    }
+
+    /// Set filters that determine which array elements to update
+    pub fn array_filters<I: IntoIterator<Item = ArrayFilter<M>>>(mut self, filters: I) -> Self {
+        let filters = filters.into_iter().map(Into::into).collect();
+        self.opts.get_or_insert_with(Default::default).array_filters = Some(filters);
         self
     }
 }
@@ -42,17 +382,23 @@ where
     M: Serialize,
 {
     type IntoFuture = UpdateOneFuture;
-    type Output = Result<()>;
+    type Output = Result<UpdateResult>;
 
     fn into_future(self) -> Self::IntoFuture {
         let opts = self.opts;
         let filter = self.filter;
         let update = self.update;
+        let session = self.session;
+        let db_name = self.db_name;
         UpdateOneFuture(Box::pin(async move {
-            let db = get_database(M::DB_NAME)?;
+            let db = model_database::<M>(db_name).await?;
             let col = db.collection::<M>(M::MODEL_NAME);
-            let _ = col.update_one(filter, update).with_options(opts).await?;
-            Ok(())
+            let action = col.update_one(filter, update).with_options(opts);
+            let res = match session {
+                Some(tx) => action.session(&mut *tx.lock().await).await?,
+                None => action.await?,
+            };
+            Ok(res.into())
         }))
     }
 }
@@ -60,10 +406,10 @@ where
 /// UpdateOneFuture
 ///
 /// Represents the executor of the update one operation
-pub struct UpdateOneFuture(BoxFut<()>);
+pub struct UpdateOneFuture(BoxFut<UpdateResult>);
 
 impl Future for UpdateOneFuture {
-    type Output = Result<()>;
+    type Output = Result<UpdateResult>;
 
     fn poll(
         self: std::pin::Pin<&mut Self>,
@@ -80,28 +426,71 @@ pub struct UpdateMany<M: Model> {
     opts: Option<UpdateOptions>,
     filter: Document,
     update: Document,
+    session: Option<Transaction>,
+    db_name: Option<String>,
     __marker: std::marker::PhantomData<M>,
 }
 
 impl<M: Model> UpdateMany<M> {
     /// Create a new update many operation
-    pub fn new(filter: Document, update: Vec<(String, Bson)>) -> Self {
+    pub fn new(filter: Document, update: Update<M>) -> Self {
         Self {
             opts: None,
             filter,
-            update: doc! {
-                "$set": Document::from_iter(update)
-            },
+            update: update.into(),
+            session: None,
+            db_name: None,
             __marker: std::marker::PhantomData,
         }
     }
 
     /// Set update many options
+    ///
+    /// Gives access to the driver options which are not covered by the builder methods
     pub fn opts<F>(mut self, f: F) -> Self
     where
-        F: FnOnce(UpdateOptionsBuilder<((), (), (), (), (), (), (), ())>) -> UpdateOptions,
+        F: FnOnce(&mut UpdateOptions),
     {
-        self.opts = Some(f(UpdateOptions::builder()));
+        f(self.opts.get_or_insert_with(Default::default));
+        self
+    }
+
+    /// Use the index of the model
+    pub fn hint(mut self, index: ModelIndex<M>) -> Self {
+        self.opts.get_or_insert_with(Default::default).hint = Some(index.into());
+        self
+    }
+
+    /// Set the collation
+    pub fn collation(mut self, collation: Collation) -> Self {
+        self.opts.get_or_insert_with(Default::default).collation = Some(collation);
+        self
+    }
+
+    /// Attach comment to the operation
+    pub fn comment<C: Into<Bson>>(mut self, comment: C) -> Self {
+        self.opts.get_or_insert_with(Default::default).comment = Some(comment.into());
+        self
+    }
+
+    /// Run the operation in the transaction
+    pub fn session(mut self, tx: &mut Transaction) -> Self {
+        self.session = Some(tx.clone());
+        self
+    }
+
+    /// Run the operation in the database chosen at runtime
+    ///
+    /// The database is registered on first use and the model is set up in it
+    pub fn in_db<N: Into<String>>(mut self, name: N) -> Self {
+        self.db_name = Some(name.into());
+        self
+    }
+
+    /// Set filters that determine which array elements to update
+    pub fn array_filters<I: IntoIterator<Item = ArrayFilter<M>>>(mut self, filters: I) -> Self {
+        let filters = filters.into_iter().map(Into::into).collect();
+        self.opts.get_or_insert_with(Default::default).array_filters = Some(filters);
         self
     }
 }
@@ -111,17 +500,23 @@ where
     M: Serialize,
 {
     type IntoFuture = UpdateManyFuture;
-    type Output = Result<()>;
+    type Output = Result<UpdateResult>;
 
     fn into_future(self) -> Self::IntoFuture {
         let opts = self.opts;
         let filter = self.filter;
         let update = self.update;
+        let session = self.session;
+        let db_name = self.db_name;
         UpdateManyFuture(Box::pin(async move {
-            let db = get_database(M::DB_NAME)?;
+            let db = model_database::<M>(db_name).await?;
             let col = db.collection::<M>(M::MODEL_NAME);
-            let _ = col.update_many(filter, update).with_options(opts).await?;
-            Ok(())
+            let action = col.update_many(filter, update).with_options(opts);
+            let res = match session {
+                Some(tx) => action.session(&mut *tx.lock().await).await?,
+                None => action.await?,
+            };
+            Ok(res.into())
         }))
     }
 }
@@ -129,12 +524,138 @@ where
 /// UpdateManyFuture
 ///
 /// Represents the executor of the update many operation
-pub struct UpdateManyFuture(BoxFut<()>);
+pub struct UpdateManyFuture(BoxFut<UpdateResult>);
 
 impl Future for UpdateManyFuture {
-    type Output = Result<()>;
+    type Output = Result<UpdateResult>;
 
     fn poll(self: std::pin::Pin<&mut Self>, cx: &mut std::task::Context<'_>) -> std::task::Poll<Self::Output> {
         self.get_mut().0.as_mut().poll(cx)
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        testing::{Age, LastSeen, Name, Nickname, Scores, User},
+        traits::{SetAble, Updatable},
+    };
+
+    #[test]
+    fn update_groups_fields_by_operator() {
+        let update = Age
+            .inc(1)
+            .and(Age.max(9))
+            .and(Name.set("a"))
+            .and(Age.inc(2));
+        assert_eq!(
+            Document::from(update),
+            doc! {
+                "$inc": {"age": 2},
+                "$max": {"age": 9},
+                "$set": {"name": "a"},
+            }
+        );
+    }
+
+    #[test]
+    fn update_from_sets() {
+        let update = Update::<User>::from(vec![Name.set("a"), Age.set(3)]);
+        assert_eq!(
+            Document::from(update),
+            doc! {"$set": {"name": "a", "age": 3}}
+        );
+        assert!(Update::<User>::from(vec![]).is_empty());
+    }
+
+    #[test]
+    fn field_update_operators() {
+        let update = Nickname
+            .unset()
+            .and(Name.rename("full_name"))
+            .and(LastSeen.current_date())
+            .and(Age.set_on_insert(18))
+            .and(Age.mul(2))
+            .and(Age.min(1));
+        assert_eq!(
+            Document::from(update),
+            doc! {
+                "$unset": {"nickname": ""},
+                "$rename": {"name": "full_name"},
+                "$currentDate": {"last_seen": true},
+                "$setOnInsert": {"age": 18},
+                "$mul": {"age": 2},
+                "$min": {"age": 1},
+            }
+        );
+    }
+
+    #[test]
+    fn push_each_modifiers() {
+        let update: Update<User> = Scores
+            .push_each([3, 1])
+            .slice(-5)
+            .sort_desc()
+            .position(0)
+            .into();
+        assert_eq!(
+            Document::from(update),
+            doc! {"$push": {"scores": {
+                "$each": [3, 1],
+                "$slice": -5,
+                "$sort": -1,
+                "$position": 0,
+            }}}
+        );
+    }
+
+    #[test]
+    fn array_update_operators() {
+        let update = Scores
+            .push(1)
+            .and(Scores.pull_all([2, 3]))
+            .and(Scores.add_to_set(4))
+            .and(Scores.pop_front());
+        assert_eq!(
+            Document::from(update),
+            doc! {
+                "$push": {"scores": 1},
+                "$pullAll": {"scores": [2, 3]},
+                "$addToSet": {"scores": 4},
+                "$pop": {"scores": -1},
+            }
+        );
+    }
+
+    #[test]
+    fn positional_paths() {
+        let update = Scores
+            .first_matched()
+            .set(1)
+            .and(Scores.all_elements().inc(2))
+            .and(Scores.elem("low").positional().unset());
+        assert_eq!(
+            Document::from(update),
+            doc! {
+                "$set": {"scores.$": 1},
+                "$inc": {"scores.$[]": 2},
+                "$unset": {"scores.$[low]": ""},
+            }
+        );
+    }
+
+    #[test]
+    fn array_filters_are_set_on_the_options() {
+        let low = Scores.elem("low");
+        let op = UpdateMany::<User>::new(doc! {}, low.positional().set(0))
+            .array_filters([low.lt(5), Scores.elem("some").is_in([1, 2])]);
+        assert_eq!(
+            op.opts.and_then(|opts| opts.array_filters),
+            Some(vec![
+                doc! {"low": {"$lt": 5}},
+                doc! {"some": {"$in": [1, 2]}},
+            ])
+        );
+    }
+}
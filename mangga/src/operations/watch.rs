@@ -0,0 +1,281 @@
+use crate::{
+    db::model_database,
+    traits::{AsFilter, Model},
+    types::{as_id, BoxStream, ID},
+    Error,
+    Result,
+};
+use bson::{doc, Bson, Document};
+use futures::{future, stream, Stream, TryStreamExt};
+use mongodb::{
+    change_stream::event::{ChangeStreamEvent, OperationType, ResumeToken},
+    options::{ChangeStreamOptions, FullDocumentBeforeChangeType, FullDocumentType},
+};
+use serde::Deserialize;
+use std::time::Duration;
+
+/// ChangeEvent
+///
+/// Represents a typed change of the model
+#[derive(Debug, Clone)]
+pub enum ChangeEvent<M> {
+    /// Document was inserted
+    Insert(M),
+    /// Document was updated
+    Update {
+        /// Id of the updated document
+        id: Bson,
+        /// Fields set by the update
+        updated_fields: Document,
+        /// Fields removed by the update
+        removed_fields: Vec<String>,
+        /// Current document, available with the full document lookup
+        full_document: Option<M>,
+    },
+    /// Document was replaced
+    Replace(M),
+    /// Document was deleted
+    Delete(Bson),
+    /// Stream was invalidated, e.g. the collection was dropped
+    Invalidate,
+}
+
+impl<M> ChangeEvent<M> {
+    /// Get the id of the updated or deleted document as `ID`
+    ///
+    /// `None` for other events or when the id is not an object id
+    pub fn id(&self) -> Option<ID> {
+        match self {
+            Self::Update { id, .. } | Self::Delete(id) => as_id(id),
+            _ => None,
+        }
+    }
+}
+
+/// Change
+///
+/// Represents a change event with its resume token
+#[derive(Debug, Clone)]
+pub struct Change<M> {
+    /// The change event
+    pub event: ChangeEvent<M>,
+    /// Token to resume the stream after this event
+    pub resume_token: ResumeToken,
+}
+
+/// Get id from the document key
+fn document_id(key: Option<Document>) -> Result<Bson> {
+    key.and_then(|mut key| key.remove("_id"))
+        .ok_or(Error::NotFound)
+}
+
+impl<M> TryFrom<ChangeStreamEvent<M>> for Change<M> {
+    type Error = Error;
+
+    fn try_from(value: ChangeStreamEvent<M>) -> Result<Self> {
+        let event = match value.operation_type {
+            OperationType::Insert => {
+                ChangeEvent::Insert(value.full_document.ok_or(Error::NotFound)?)
+            }
+            OperationType::Replace => {
+                ChangeEvent::Replace(value.full_document.ok_or(Error::NotFound)?)
+            }
+            OperationType::Update => {
+                let (updated_fields, removed_fields) = value
+                    .update_description
+                    .map(|d| (d.updated_fields, d.removed_fields))
+                    .unwrap_or_default();
+                ChangeEvent::Update {
+                    id: document_id(value.document_key)?,
+                    updated_fields,
+                    removed_fields,
+                    full_document: value.full_document,
+                }
+            }
+            OperationType::Delete => ChangeEvent::Delete(document_id(value.document_key)?),
+            _ => ChangeEvent::Invalidate,
+        };
+
+        Ok(Self {
+            event,
+            resume_token: value.id,
+        })
+    }
+}
+
+/// Prefix field names of the filter with `fullDocument.`
+fn full_document_filter(filter: Document) -> Document {
+    filter
+        .into_iter()
+        .map(|(key, value)| match (key.as_str(), value) {
+            ("$and" | "$or" | "$nor", Bson::Array(clauses)) => {
+                let clauses = clauses
+                    .into_iter()
+                    .map(|clause| match clause {
+                        Bson::Document(d) => Bson::Document(full_document_filter(d)),
+                        other => other,
+                    })
+                    .collect::<Vec<_>>();
+                (key, Bson::Array(clauses))
+            }
+            (name, value) if name.starts_with('$') => (key, value),
+            (name, value) => (format!("fullDocument.{}", name), value),
+        })
+        .collect()
+}
+
+/// Watch
+///
+/// Represents the change stream of the model
+///
+/// The stream is opened on the first poll
+pub struct Watch<M: Model> {
+    pipeline: Vec<Document>,
+    opts: Option<ChangeStreamOptions>,
+    db_name: Option<String>,
+    stream: Option<BoxStream<Change<M>>>,
+}
+
+impl<M: Model> Watch<M> {
+    /// Create a new change stream
+    pub fn new() -> Self {
+        Self {
+            pipeline: vec![],
+            opts: None,
+            db_name: None,
+            stream: None,
+        }
+    }
+
+    /// Set change stream options
+    ///
+    /// Gives access to the driver options which are not covered by the builder methods
+    pub fn opts<F>(mut self, f: F) -> Self
+    where
+        F: FnOnce(&mut ChangeStreamOptions),
+    {
+        f(self.opts.get_or_insert_with(Default::default));
+        self
+    }
+
+    /// Watch the database chosen at runtime
+    pub fn in_db<N: Into<String>>(mut self, name: N) -> Self {
+        self.db_name = Some(name.into());
+        self
+    }
+
+    /// Only emit changes of the documents matching the filter
+    ///
+    /// The filter is applied to the full document, so the full document lookup is
+    /// enabled for updates and deletes are not emitted
+    pub fn filter<F: AsFilter>(mut self, filter: F) -> Self {
+        let opts = self.opts.get_or_insert_with(Default::default);
+        if opts.full_document.is_none() {
+            opts.full_document = Some(FullDocumentType::UpdateLookup);
+        }
+        let filter = full_document_filter(filter.as_filter());
+        self.pipeline.push(doc! {"$match": filter});
+        self
+    }
+
+    /// Set how the full document of updates is returned
+    pub fn full_document(mut self, full_document: FullDocumentType) -> Self {
+        self.opts.get_or_insert_with(Default::default).full_document = Some(full_document);
+        self
+    }
+
+    /// Set how the document before the change is returned
+    pub fn full_document_before_change(mut self, before: FullDocumentBeforeChangeType) -> Self {
+        self.opts
+            .get_or_insert_with(Default::default)
+            .full_document_before_change = Some(before);
+        self
+    }
+
+    /// Resume the stream after the token
+    pub fn resume_after(mut self, token: ResumeToken) -> Self {
+        self.opts.get_or_insert_with(Default::default).resume_after = Some(token);
+        self
+    }
+
+    /// Start the stream after the token, also works after an invalidate event
+    pub fn start_after(mut self, token: ResumeToken) -> Self {
+        self.opts.get_or_insert_with(Default::default).start_after = Some(token);
+        self
+    }
+
+    /// Set the number of documents fetched per batch
+    pub fn batch_size(mut self, size: u32) -> Self {
+        self.opts.get_or_insert_with(Default::default).batch_size = Some(size);
+        self
+    }
+
+    /// Set the maximum time the server waits for new changes
+    pub fn max_await_time(mut self, max_await_time: Duration) -> Self {
+        self.opts
+            .get_or_insert_with(Default::default)
+            .max_await_time = Some(max_await_time);
+        self
+    }
+}
+
+impl<M: Model> Watch<M>
+where
+    M: for<'de> Deserialize<'de> + Unpin,
+{
+    /// Open the change stream
+    fn open(&mut self) -> BoxStream<Change<M>> {
+        let pipeline = std::mem::take(&mut self.pipeline);
+        let opts = self.opts.take();
+        let db_name = self.db_name.take();
+        let stream = async move {
+            let col = model_database::<M>(db_name)
+                .await?
+                .collection::<M>(M::MODEL_NAME);
+            let stream = col.watch().pipeline(pipeline).with_options(opts).await?;
+            let stream = stream
+                .map_err(Error::from)
+                .try_filter(|event| {
+                    future::ready(!matches!(
+                        event.operation_type,
+                        OperationType::Drop
+                            | OperationType::Rename
+                            | OperationType::DropDatabase
+                            | OperationType::Other(_)
+                    ))
+                })
+                .and_then(|event| future::ready(Change::try_from(event)));
+            Ok::<_, Error>(stream)
+        };
+
+        Box::pin(stream::once(stream).try_flatten())
+    }
+}
+
+impl<M: Model> Default for Watch<M> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<M: Model> Stream for Watch<M>
+where
+    M: for<'de> Deserialize<'de> + Unpin,
+{
+    type Item = Result<Change<M>>;
+
+    fn poll_next(
+        self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        if this.stream.is_none() {
+            this.stream = Some(this.open());
+        }
+        this.stream
+            .as_mut()
+            .expect("stream is opened")
+            .as_mut()
+            .poll_next(cx)
+    }
+}
@@ -0,0 +1,88 @@
+use crate::{
+    traits::{Field, Model},
+    types::{DateTime, IndexSpec, ID},
+};
+
+/// Model used by the unit tests
+#[derive(Debug, Clone)]
+pub struct User;
+
+impl Model for User {
+    const MODEL_NAME: &'static str = "users";
+    const DB_NAME: &'static str = "test";
+    const INDEXES: &'static [IndexSpec] = &[];
+
+    fn id(&self) -> impl Into<ID> {
+        ID::default()
+    }
+}
+
+pub struct Id;
+
+impl Field for Id {
+    type Model = User;
+    const NAME: &'static str = "_id";
+    type Type = ID;
+}
+
+pub struct Name;
+
+impl Field for Name {
+    type Model = User;
+    const NAME: &'static str = "name";
+    type Type = String;
+}
+
+pub struct Age;
+
+impl Field for Age {
+    type Model = User;
+    const NAME: &'static str = "age";
+    type Type = i32;
+}
+
+pub struct Nickname;
+
+impl Field for Nickname {
+    type Model = User;
+    const NAME: &'static str = "nickname";
+    type Type = Option<String>;
+}
+
+pub struct LastSeen;
+
+impl Field for LastSeen {
+    type Model = User;
+    const NAME: &'static str = "last_seen";
+    type Type = DateTime;
+}
+
+pub struct Scores;
+
+impl Field for Scores {
+    type Model = User;
+    const NAME: &'static str = "scores";
+    type Type = Vec<i32>;
+}
+
+/// Second model used by the unit tests
+#[derive(Debug, Clone)]
+pub struct Post;
+
+impl Model for Post {
+    const MODEL_NAME: &'static str = "posts";
+    const DB_NAME: &'static str = "test";
+    const INDEXES: &'static [IndexSpec] = &[];
+
+    fn id(&self) -> impl Into<ID> {
+        ID::default()
+    }
+}
+
+pub struct Author;
+
+impl Field for Author {
+    type Model = Post;
+    const NAME: &'static str = "author_id";
+    type Type = ID;
+}
@@ -0,0 +1,267 @@
+use super::{Array, Field};
+use serde::{Deserialize, Serialize};
+use std::marker::PhantomData;
+
+/// Embedded
+///
+/// Represents a type holding an embedded document
+pub trait Embedded {
+    /// Struct of the embedded document
+    type Inner;
+}
+
+impl<T: Embedded> Embedded for Option<T> {
+    type Inner = T::Inner;
+}
+
+impl<T: Embedded> Embedded for Vec<T> {
+    type Inner = T::Inner;
+}
+
+/// EmbeddedField
+///
+/// Represents a field of an embedded document
+pub trait EmbeddedField {
+    /// Struct of the embedded document
+    type Parent;
+
+    /// Name of the field
+    const NAME: &'static str;
+
+    /// Type of the field
+    type Type: Serialize + for<'de> Deserialize<'de>;
+}
+
+/// Path
+///
+/// Represents a field of an embedded document reached from a field of the model
+#[derive(Debug)]
+pub struct Path<P, C> {
+    __marker: PhantomData<(P, C)>,
+}
+
+impl<P, C> Clone for Path<P, C> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<P, C> Copy for Path<P, C> {}
+
+/// Longest dotted path of an embedded field
+const MAX_PATH: usize = 256;
+
+/// Dotted path joined at compile time
+struct Joined {
+    buf: [u8; MAX_PATH],
+    len: usize,
+}
+
+/// Join the parent path and the field name, an element parent has no path of its own
+const fn join(parent: &str, child: &str) -> Joined {
+    let (parent, child) = (parent.as_bytes(), child.as_bytes());
+    let sep = if parent.is_empty() { 0 } else { 1 };
+    let len = parent.len() + sep + child.len();
+    assert!(len <= MAX_PATH, "embedded field path is too long");
+
+    let mut buf = [0; MAX_PATH];
+    let mut i = 0;
+    while i < parent.len() {
+        buf[i] = parent[i];
+        i += 1;
+    }
+    if sep == 1 {
+        buf[i] = b'.';
+        i += 1;
+    }
+    let mut j = 0;
+    while j < child.len() {
+        buf[i + j] = child[j];
+        j += 1;
+    }
+    Joined { buf, len }
+}
+
+impl Joined {
+    /// Get the joined path
+    const fn as_str(&self) -> &str {
+        match std::str::from_utf8(self.buf.split_at(self.len).0) {
+            Ok(path) => path,
+            Err(_) => panic!("embedded field path is not valid utf-8"),
+        }
+    }
+}
+
+impl<P, C> Path<P, C>
+where
+    P: Field,
+    C: EmbeddedField,
+{
+    const JOINED: Joined = join(P::NAME, C::NAME);
+}
+
+impl<P, C> Field for Path<P, C>
+where
+    P: Field,
+    P::Type: Embedded<Inner = C::Parent>,
+    C: EmbeddedField,
+{
+    type Model = P::Model;
+    const NAME: &'static str = Self::JOINED.as_str();
+    type Type = C::Type;
+}
+
+/// Element
+///
+/// Represents the element of an array field, its paths are relative to the element
+#[derive(Debug)]
+pub struct Element<F> {
+    __marker: PhantomData<F>,
+}
+
+impl<F> Element<F> {
+    /// Create a new element
+    pub(crate) fn new() -> Self {
+        Self {
+            __marker: PhantomData,
+        }
+    }
+}
+
+impl<F> Clone for Element<F> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<F> Copy for Element<F> {}
+
+impl<F> Field for Element<F>
+where
+    F: Field,
+    F::Type: Array,
+    <F::Type as Array>::Item: Serialize + for<'de> Deserialize<'de>,
+{
+    type Model = F::Model;
+    const NAME: &'static str = "";
+    type Type = <F::Type as Array>::Item;
+}
+
+/// Nestable
+///
+/// Allows a field holding an embedded document to reach the fields of the document
+pub trait Nestable: Field + Sized {
+    /// Get the path to a field of the embedded document
+    fn then<C>(self, _child: C) -> Path<Self, C>
+    where
+        C: EmbeddedField,
+        Self::Type: Embedded<Inner = C::Parent>,
+    {
+        Path {
+            __marker: PhantomData,
+        }
+    }
+}
+
+impl<T> Nestable for T where T: Field {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        testing::User,
+        traits::{AsFilter, Queryable},
+    };
+    use bson::doc;
+
+    #[derive(Serialize, Deserialize)]
+    struct Address;
+
+    impl Embedded for Address {
+        type Inner = Self;
+    }
+
+    #[derive(Serialize, Deserialize)]
+    struct Geo;
+
+    impl Embedded for Geo {
+        type Inner = Self;
+    }
+
+    #[derive(Clone, Copy)]
+    struct Home;
+
+    impl Field for Home {
+        type Model = User;
+        const NAME: &'static str = "home";
+        type Type = Option<Address>;
+    }
+
+    #[derive(Clone, Copy)]
+    struct City;
+
+    impl EmbeddedField for City {
+        type Parent = Address;
+        const NAME: &'static str = "city";
+        type Type = String;
+    }
+
+    #[derive(Clone, Copy)]
+    struct Location;
+
+    impl EmbeddedField for Location {
+        type Parent = Address;
+        const NAME: &'static str = "loc";
+        type Type = Geo;
+    }
+
+    #[derive(Clone, Copy)]
+    struct Lat;
+
+    impl EmbeddedField for Lat {
+        type Parent = Geo;
+        const NAME: &'static str = "lat";
+        type Type = f64;
+    }
+
+    #[derive(Clone, Copy)]
+    struct Homes;
+
+    impl Field for Homes {
+        type Model = User;
+        const NAME: &'static str = "homes";
+        type Type = Vec<Address>;
+    }
+
+    fn name<F: Field>(_: F) -> &'static str {
+        F::NAME
+    }
+
+    #[test]
+    fn join_adds_a_single_dot() {
+        assert_eq!(join("home", "city").as_str(), "home.city");
+        assert_eq!(join("a.b", "c").as_str(), "a.b.c");
+    }
+
+    #[test]
+    fn paths_are_joined_from_the_model_field() {
+        assert_eq!(name(Home.then(City)), "home.city");
+        assert_eq!(name(Home.then(Location).then(Lat)), "home.loc.lat");
+    }
+
+    #[test]
+    fn element_paths_are_relative_to_the_element() {
+        let filter = Homes
+            .elem_match(|home| {
+                home.then(City).eq("Bandung") & home.then(Location).then(Lat).gt(1.0)
+            })
+            .as_filter();
+        assert_eq!(
+            filter,
+            doc! {"homes": {"$elemMatch": {"$and": [
+                {"city": {"$eq": "Bandung"}},
+                {"loc.lat": {"$gt": 1.0}},
+            ]}}}
+        );
+    }
+}
@@ -1,9 +1,13 @@
+mod embedded;
 mod models;
 mod ops;
 mod self_ops;
 mod others;
+mod schema;
 
+pub use embedded::*;
 pub use models::*;
 pub use ops::*;
 pub use self_ops::*;
 pub use others::*;
+pub use schema::*;
@@ -1,13 +1,12 @@
 use crate::{
-    db::get_database,
-    types::{BoxFut, ID},
+    db::{get_connection, DEFAULT_CONNECTION},
+    types::{BoxFut, CollectionSpec, IndexPlan, IndexPolicy, IndexSpec, ID},
+    Error,
     Result,
 };
 use bson::{doc, Document};
 use futures::TryStreamExt;
-use mongodb::{options::IndexOptions, IndexModel};
 use serde::{Deserialize, Serialize};
-use std::time::Duration;
 
 /// Model
 ///
@@ -19,26 +18,43 @@ pub trait Model: Clone + Send + Sync + 'static {
     /// Database name of the model
     const DB_NAME: &'static str;
 
-    /// Indexes
-    ///
-    /// Represents the indexes of the model with this order
-    /// field, name, score, unique, exp
-    const INDEXES: &'static [(&'static str, &'static str, i32, bool, Option<u64>)];
+    /// Name of the connection of the model
+    const CONNECTION: &'static str = DEFAULT_CONNECTION;
+
+    /// Indexes of the model
+    const INDEXES: &'static [IndexSpec];
+
+    /// Options of the collection
+    const COLLECTION: CollectionSpec = CollectionSpec::DEFAULT;
+
+    /// How the setup treats the indexes of the collection
+    const INDEX_POLICY: IndexPolicy = IndexPolicy::DEFAULT;
 
     /// Get id
     fn id(&self) -> impl Into<ID>;
 
+    /// Get the `$jsonSchema` validator installed by the setup
+    fn json_schema() -> Option<Document> {
+        None
+    }
+
+    /// Get mongodb database from the connection of the model
+    #[tracing::instrument(level = tracing::Level::DEBUG)]
+    fn get_database() -> Result<mongodb::Database> {
+        get_connection(Self::CONNECTION)?.database(Self::DB_NAME)
+    }
+
     /// Get mongodb collection
     #[tracing::instrument(level = tracing::Level::DEBUG)]
     fn get_collection() -> Result<mongodb::Collection<Self>> {
-        Ok(get_database(Self::DB_NAME)?.collection(Self::MODEL_NAME))
+        Ok(Self::get_database()?.collection(Self::MODEL_NAME))
     }
 
     /// Runs an aggregation pipeline
     #[tracing::instrument(level = tracing::Level::DEBUG)]
     fn aggregate(pipeline: Vec<Document>) -> BoxFut<Vec<Document>> {
         Box::pin(async move {
-            let db = get_database(Self::DB_NAME)?;
+            let db = Self::get_database()?;
             let col = db.collection::<Self>(Self::MODEL_NAME);
             let res = col
                 .aggregate(pipeline)
@@ -51,83 +67,99 @@ pub trait Model: Clone + Send + Sync + 'static {
 
     /// Setup the model
     #[tracing::instrument(level = tracing::Level::DEBUG)]
-    fn setup() -> BoxFut<()> {
-        Box::pin(async move {
-            let db = get_database(Self::DB_NAME)?;
-            let cols = db.list_collection_names().await?;
-            if !cols.contains(&Self::MODEL_NAME.to_string()) {
-                db.create_collection(Self::MODEL_NAME).await?;
-            }
-            let col = db.collection::<Self>(Self::MODEL_NAME);
-            let mut all_indexes = col
-                .list_index_names()
-                .await?
-                .into_iter()
-                .filter(|n| n != "_id_")
-                .collect::<Vec<_>>();
-            all_indexes.sort();
-            let mut local_indexes = Self::INDEXES
-                .into_iter()
-                .map(|(_, name, _, _, _)| name.to_string())
-                .collect::<Vec<_>>();
-            local_indexes.sort();
-            if all_indexes == local_indexes {
-                return Ok(());
-            }
+    fn setup() -> BoxFut<IndexPlan> {
+        Box::pin(async move { Self::setup_in(Self::get_database()?).await })
+    }
 
-            let match_indexes = all_indexes
-                .iter()
-                .filter(|n| local_indexes.contains(n))
-                .map(|s| s.to_string())
-                .collect::<Vec<_>>();
-            let unmatch_indexes = all_indexes
-                .iter()
-                .filter(|n| !local_indexes.contains(n))
-                .map(|s| s.to_string())
-                .collect::<Vec<_>>();
-            let new_indexes = local_indexes
-                .iter()
-                .filter(|n| !match_indexes.contains(n))
-                .map(|s| s.to_string())
-                .collect::<Vec<_>>();
-
-            if !unmatch_indexes.is_empty() {
-                for name in unmatch_indexes {
-                    col.drop_index(name).await?;
-                }
-            }
+    /// Plan the index changes of the model without executing them
+    #[tracing::instrument(level = tracing::Level::DEBUG)]
+    fn index_plan() -> BoxFut<IndexPlan> {
+        Box::pin(async move {
+            let col = Self::get_collection()?;
+            let existing = col.list_indexes().await?.try_collect::<Vec<_>>().await?;
+            Ok(IndexPlan::new(Self::INDEXES, &existing, Self::INDEX_POLICY))
+        })
+    }
 
-            if !new_indexes.is_empty() {
-                let mut indexes = vec![];
-                for name in new_indexes {
-                    let index = Self::INDEXES.iter().find(|(_, n, _, _, _)| *n == &name);
-                    if let Some((field, name, score, unique, exp)) = index {
-                        let field = field.to_string();
-                        let index_options_builder = IndexOptions::builder()
-                            .name(name.to_string())
-                            .unique(*unique);
-
-                        let index_options = if let Some(exp) = exp {
-                            index_options_builder
-                                .expire_after(Duration::from_secs(*exp))
-                                .build()
-                        } else {
-                            index_options_builder.build()
-                        };
-
-                        indexes.push(
-                            IndexModel::builder()
-                                .keys(doc! {field: score})
-                                .options(index_options)
-                                .build(),
-                        );
+    /// Setup the model in the given database
+    ///
+    /// With `dry_run` nothing is written, the plan is only reported
+    #[tracing::instrument(skip(db), level = tracing::Level::DEBUG)]
+    fn setup_in(db: mongodb::Database) -> BoxFut<IndexPlan> {
+        Box::pin(async move {
+            let existing = db
+                .list_collections()
+                .filter(doc! { "name": Self::MODEL_NAME })
+                .await?
+                .try_next()
+                .await?;
+            let validator = Self::json_schema().map(|schema| doc! { "$jsonSchema": schema });
+            let dry_run = Self::INDEX_POLICY.dry_run;
+            let exists = match existing {
+                Some(spec) => {
+                    // collections of models without options are left as they are
+                    let mismatches = if Self::COLLECTION.has_options() {
+                        Self::COLLECTION.mismatches(&spec.options)
+                    } else {
+                        vec![]
+                    };
+                    if !mismatches.is_empty() {
+                        return Err(Error::CollectionMismatch(format!(
+                            "`{}` {}",
+                            Self::MODEL_NAME,
+                            mismatches.join(", ")
+                        )));
+                    }
+                    let level = Self::COLLECTION.validation_level();
+                    let action = Self::COLLECTION.validation_action();
+                    let changed = validator.is_some()
+                        && (spec.options.validator != validator
+                            || (level.is_some() && level != spec.options.validation_level)
+                            || (action.is_some() && action != spec.options.validation_action));
+                    if changed && !dry_run {
+                        let mut command =
+                            doc! { "collMod": Self::MODEL_NAME, "validator": validator };
+                        if let Some(level) = Self::COLLECTION.validation_level {
+                            command.insert("validationLevel", level);
+                        }
+                        if let Some(action) = Self::COLLECTION.validation_action {
+                            command.insert("validationAction", action);
+                        }
+                        db.run_command(command).await?;
                     }
+                    true
+                }
+                None if dry_run => false,
+                None => {
+                    let mut opts = Self::COLLECTION.options();
+                    opts.validator = validator;
+                    db.create_collection(Self::MODEL_NAME)
+                        .with_options(opts)
+                        .await?;
+                    true
                 }
+            };
+            let col = db.collection::<Self>(Self::MODEL_NAME);
+            let existing = if exists {
+                col.list_indexes().await?.try_collect::<Vec<_>>().await?
+            } else {
+                vec![]
+            };
+            let mut plan = IndexPlan::new(Self::INDEXES, &existing, Self::INDEX_POLICY);
+            if dry_run || plan.is_empty() {
+                return Ok(plan);
+            }
 
+            for name in &plan.drop {
+                col.drop_index(name).await?;
+            }
+            if !plan.create.is_empty() {
+                let indexes = plan.create.iter().map(IndexSpec::model).collect::<Vec<_>>();
                 col.create_indexes(indexes).await?;
             }
+            plan.executed = true;
 
-            Ok(())
+            Ok(plan)
         })
     }
 }
@@ -135,7 +167,45 @@ pub trait Model: Clone + Send + Sync + 'static {
 /// Dsl
 ///
 /// Represents the dsl of the model
-pub trait Dsl<T: Model> {}
+pub trait Dsl<T: Model> {
+    /// Database chosen at runtime for the operations
+    fn db_name(&self) -> Option<&str> {
+        None
+    }
+
+    /// Scope the operations to the database chosen at runtime
+    fn in_db<N: Into<String>>(&self, name: N) -> InDb<T>
+    where
+        Self: Sized,
+    {
+        InDb::new(name)
+    }
+}
+
+/// InDb
+///
+/// Represents the dsl of the model scoped to the database chosen at runtime
+#[derive(Debug, Clone)]
+pub struct InDb<T: Model> {
+    name: String,
+    __marker: std::marker::PhantomData<T>,
+}
+
+impl<T: Model> InDb<T> {
+    /// Create a new scoped dsl
+    pub fn new<N: Into<String>>(name: N) -> Self {
+        Self {
+            name: name.into(),
+            __marker: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<T: Model> Dsl<T> for InDb<T> {
+    fn db_name(&self) -> Option<&str> {
+        Some(&self.name)
+    }
+}
 
 /// Field
 ///
@@ -150,3 +220,40 @@ pub trait Field {
     /// Type of the field
     type Type: Serialize + for<'de> Deserialize<'de>;
 }
+
+/// Projection
+///
+/// Represents a partial struct of the model
+pub trait Projection {
+    /// Model type of the projection
+    type Model: Model;
+
+    /// Get the projection document
+    fn projection() -> Document;
+}
+
+/// Function to check the projected field against the model and get its name
+///
+/// Fails to compile when the projection reads the field from another key than the model stores
+pub const fn project_field<M, F, T>(_field: &F, key: &str) -> &'static str
+where
+    M: Model,
+    F: Field<Model = M, Type = T>,
+{
+    let name = F::NAME.as_bytes();
+    let key = key.as_bytes();
+    let mut same = name.len() == key.len();
+    let mut i = 0;
+    while same && i < name.len() {
+        same = name[i] == key[i];
+        i += 1;
+    }
+    if !same {
+        panic!(
+            "the projected field is stored under another key in the model, set the key with \
+             `#[serde(rename = \"...\")]`"
+        );
+    }
+
+    F::NAME
+}
@@ -1,5 +1,6 @@
 use super::{AsFilter, Dsl, Model};
 use crate::operations::{
+    Aggregate,
     Count,
     DeleteMany,
     DeleteOne,
@@ -7,10 +8,21 @@ use crate::operations::{
     FindOne,
     InsertMany,
     InsertOne,
+    Update,
     UpdateMany,
     UpdateOne,
+    Watch,
 };
-use bson::Bson;
+
+/// Scope the operation to the database of the dsl
+macro_rules! scoped {
+    ($dsl:expr, $op:expr) => {
+        match $dsl.db_name() {
+            Some(name) => $op.in_db(name),
+            None => $op,
+        }
+    };
+}
 
 /// Ops
 ///
@@ -35,13 +47,19 @@ pub trait Ops<M: Model> {
     fn delete_many<F: AsFilter>(&self, filter: F) -> DeleteMany<M>;
 
     /// Update one model
-    fn update_one<F: AsFilter>(&self, filter: F, update: Vec<(String, Bson)>) -> UpdateOne<M>;
+    fn update_one<F: AsFilter, U: Into<Update<M>>>(&self, filter: F, update: U) -> UpdateOne<M>;
 
     /// Update many models
-    fn update_many<F: AsFilter>(&self, filter: F, update: Vec<(String, Bson)>) -> UpdateMany<M>;
+    fn update_many<F: AsFilter, U: Into<Update<M>>>(&self, filter: F, update: U) -> UpdateMany<M>;
 
     /// Count the number of models
     fn count<F: AsFilter>(&self, filter: F) -> Count<M>;
+
+    /// Start an aggregation pipeline
+    fn aggregate(&self) -> Aggregate<M>;
+
+    /// Watch the changes of the model
+    fn watch(&self) -> Watch<M>;
 }
 
 impl<M, D> Ops<M> for D
@@ -51,46 +69,56 @@ where
 {
     #[tracing::instrument(skip_all, level = tracing::Level::DEBUG)]
     fn insert_many<T: IntoIterator<Item = M>>(&self, models: T) -> InsertMany<M> {
-        InsertMany::new(models.into_iter().collect())
+        scoped!(self, InsertMany::new(models.into_iter().collect()))
     }
 
     #[tracing::instrument(skip_all, level = tracing::Level::DEBUG)]
     fn insert_one<'a>(&self, model: &'a M) -> InsertOne<'a, M> {
-        InsertOne::new(model)
+        scoped!(self, InsertOne::new(model))
     }
 
     #[tracing::instrument(skip_all, level = tracing::Level::DEBUG)]
     fn find_one<F: AsFilter>(&self, filter: F) -> FindOne<M> {
-        FindOne::new(filter.as_filter())
+        scoped!(self, FindOne::new(filter.as_filter()))
     }
 
     #[tracing::instrument(skip_all, level = tracing::Level::DEBUG)]
     fn find_many<F: AsFilter>(&self, filter: F) -> FindMany<M> {
-        FindMany::new(filter.as_filter())
+        scoped!(self, FindMany::new(filter.as_filter()))
     }
 
     #[tracing::instrument(skip_all, level = tracing::Level::DEBUG)]
     fn delete_one<F: AsFilter>(&self, filter: F) -> DeleteOne<M> {
-        DeleteOne::new(filter.as_filter())
+        scoped!(self, DeleteOne::new(filter.as_filter()))
     }
 
     #[tracing::instrument(skip_all, level = tracing::Level::DEBUG)]
     fn delete_many<F: AsFilter>(&self, filter: F) -> DeleteMany<M> {
-        DeleteMany::new(filter.as_filter())
+        scoped!(self, DeleteMany::new(filter.as_filter()))
     }
 
     #[tracing::instrument(skip_all, level = tracing::Level::DEBUG)]
-    fn update_one<F: AsFilter>(&self, filter: F, update: Vec<(String, Bson)>) -> UpdateOne<M> {
-        UpdateOne::new(filter.as_filter(), update)
+    fn update_one<F: AsFilter, U: Into<Update<M>>>(&self, filter: F, update: U) -> UpdateOne<M> {
+        scoped!(self, UpdateOne::new(filter.as_filter(), update.into()))
     }
 
     #[tracing::instrument(skip_all, level = tracing::Level::DEBUG)]
-    fn update_many<F: AsFilter>(&self, filter: F, update: Vec<(String, Bson)>) -> UpdateMany<M> {
-        UpdateMany::new(filter.as_filter(), update)
+    fn update_many<F: AsFilter, U: Into<Update<M>>>(&self, filter: F, update: U) -> UpdateMany<M> {
+        scoped!(self, UpdateMany::new(filter.as_filter(), update.into()))
     }
 
     #[tracing::instrument(skip_all, level = tracing::Level::DEBUG)]
     fn count<F: AsFilter>(&self, filter: F) -> Count<M> {
-        Count::new(filter.as_filter())
+        scoped!(self, Count::new(filter.as_filter()))
+    }
+
+    #[tracing::instrument(skip_all, level = tracing::Level::DEBUG)]
+    fn aggregate(&self) -> Aggregate<M> {
+        scoped!(self, Aggregate::new(vec![]))
+    }
+
+    #[tracing::instrument(skip_all, level = tracing::Level::DEBUG)]
+    fn watch(&self) -> Watch<M> {
+        scoped!(self, Watch::new())
     }
 }
@@ -1,5 +1,8 @@
-use super::Field;
-use crate::operations::{Operator, Query};
+use super::{Element, Field, Model};
+use crate::{
+    operations::{and, nor, or, Elem, Filter, Operator, Positional, PushEach, Query, Update},
+    types::DateTime,
+};
 use bson::{bson, doc, Bson, Document};
 
 /// AsFilter
@@ -7,9 +10,40 @@ use bson::{bson, doc, Bson, Document};
 /// Allows expression to be used as filter
 pub trait AsFilter {
     /// Get the expression as filter
+    #[allow(clippy::wrong_self_convention)]
     fn as_filter(self) -> Document;
 }
 
+/// ModelFilter
+///
+/// Represents a filter that belongs to a certain model
+pub trait ModelFilter: AsFilter {
+    /// Model type of the filter
+    type Model: Model;
+}
+
+/// Combinable
+///
+/// Allows filters of the same model to be combined
+pub trait Combinable: ModelFilter + Sized {
+    /// Combine with another filter using `$and`
+    fn and<F: ModelFilter<Model = Self::Model>>(self, other: F) -> Filter<Self::Model> {
+        and(self, other)
+    }
+
+    /// Combine with another filter using `$or`
+    fn or<F: ModelFilter<Model = Self::Model>>(self, other: F) -> Filter<Self::Model> {
+        or(self, other)
+    }
+
+    /// Combine with another filter using `$nor`
+    fn nor<F: ModelFilter<Model = Self::Model>>(self, other: F) -> Filter<Self::Model> {
+        nor(self, other)
+    }
+}
+
+impl<T> Combinable for T where T: ModelFilter {}
+
 /// Queryable
 ///
 /// Allows a certain field to be queried
@@ -45,6 +79,7 @@ pub trait Queryable: Field + Sized {
     }
 
     /// Create `in` query
+    #[allow(clippy::wrong_self_convention)]
     fn is_in<T: Into<Self::Type>,V: IntoIterator<Item = T>>(
         self,
         value: V,
@@ -59,6 +94,56 @@ pub trait Queryable: Field + Sized {
     ) -> Query<Self, Vec<Self::Type>> {
         Query::new(Operator::Nin, value.into_iter().map(Into::into).collect())
     }
+
+    /// Create query matching arrays containing the element
+    fn contains<V: Into<<Self::Type as Array>::Item>>(
+        self,
+        value: V,
+    ) -> Query<Self, <Self::Type as Array>::Item>
+    where
+        Self::Type: Array,
+    {
+        Query::new(Operator::Eq, value.into())
+    }
+
+    /// Create query matching arrays containing any of the elements
+    fn contains_any<T, V>(self, values: V) -> Query<Self, Vec<<Self::Type as Array>::Item>>
+    where
+        Self::Type: Array,
+        T: Into<<Self::Type as Array>::Item>,
+        V: IntoIterator<Item = T>,
+    {
+        Query::new(Operator::In, values.into_iter().map(Into::into).collect())
+    }
+
+    /// Create `all` query matching arrays containing all of the elements
+    fn all<T, V>(self, values: V) -> Query<Self, Vec<<Self::Type as Array>::Item>>
+    where
+        Self::Type: Array,
+        T: Into<<Self::Type as Array>::Item>,
+        V: IntoIterator<Item = T>,
+    {
+        Query::new(Operator::All, values.into_iter().map(Into::into).collect())
+    }
+
+    /// Create `size` query matching arrays with the number of elements
+    fn size(self, size: u32) -> Query<Self, i64>
+    where
+        Self::Type: Array,
+    {
+        Query::new(Operator::Size, size as i64)
+    }
+
+    /// Create `elemMatch` query from a filter over the element or the fields of an embedded element
+    fn elem_match<R, Fn>(self, filter: Fn) -> Query<Self, Document>
+    where
+        Self::Type: Array,
+        Element<Self>: Field<Model = Self::Model>,
+        R: ModelFilter<Model = Self::Model>,
+        Fn: FnOnce(Element<Self>) -> R,
+    {
+        Query::new(Operator::ElemMatch, filter(Element::new()).as_filter())
+    }
 }
 
 /// SetAble
@@ -79,6 +164,274 @@ where
     }
 }
 
+/// Numeric
+///
+/// Represents a type that supports arithmetic update operators
+pub trait Numeric: Into<Bson> {}
+
+impl Numeric for i32 {}
+impl Numeric for i64 {}
+impl Numeric for u32 {}
+impl Numeric for f32 {}
+impl Numeric for f64 {}
+
+/// Nullable
+///
+/// Represents a type that can be removed from the document
+pub trait Nullable {}
+
+impl<T> Nullable for Option<T> {}
+
+/// Array
+///
+/// Represents an array type
+pub trait Array {
+    /// Type of the array element
+    type Item;
+}
+
+impl<T> Array for Vec<T> {
+    type Item = T;
+}
+
+/// Temporal
+///
+/// Represents a type stored as bson date that can be set to the current date
+///
+/// `chrono::DateTime` is stored as string by its own serde, use [`DateTime`] instead
+pub trait Temporal {}
+
+impl Temporal for DateTime {}
+impl Temporal for bson::DateTime {}
+impl<T: Temporal> Temporal for Option<T> {}
+
+/// BsonDate
+///
+/// Represents a type always stored as bson date
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` is not stored as bson date",
+    note = "use `mangga::prelude::DateTime` or `bson::DateTime`"
+)]
+pub trait BsonDate {}
+
+impl BsonDate for DateTime {}
+impl BsonDate for bson::DateTime {}
+
+/// Updatable
+///
+/// Allows a certain field to be updated with update operators
+pub trait Updatable: Field + Sized {
+    /// Create `$inc` update
+    fn inc<V: Into<Self::Type>>(self, value: V) -> Update<Self::Model>
+    where
+        Self::Type: Numeric,
+    {
+        Update::with("$inc", Self::NAME, value.into())
+    }
+
+    /// Create `$mul` update
+    fn mul<V: Into<Self::Type>>(self, value: V) -> Update<Self::Model>
+    where
+        Self::Type: Numeric,
+    {
+        Update::with("$mul", Self::NAME, value.into())
+    }
+
+    /// Create `$min` update
+    fn min<V: Into<Self::Type>>(self, value: V) -> Update<Self::Model>
+    where
+        Self::Type: Into<Bson>,
+    {
+        Update::with("$min", Self::NAME, value.into())
+    }
+
+    /// Create `$max` update
+    fn max<V: Into<Self::Type>>(self, value: V) -> Update<Self::Model>
+    where
+        Self::Type: Into<Bson>,
+    {
+        Update::with("$max", Self::NAME, value.into())
+    }
+
+    /// Create `$unset` update
+    fn unset(self) -> Update<Self::Model>
+    where
+        Self::Type: Nullable,
+    {
+        Update::with("$unset", Self::NAME, "")
+    }
+
+    /// Create `$rename` update
+    fn rename<S: AsRef<str>>(self, new_name: S) -> Update<Self::Model> {
+        Update::with("$rename", Self::NAME, new_name.as_ref())
+    }
+
+    /// Create `$currentDate` update
+    fn current_date(self) -> Update<Self::Model>
+    where
+        Self::Type: Temporal,
+    {
+        Update::with("$currentDate", Self::NAME, true)
+    }
+
+    /// Create `$setOnInsert` update
+    fn set_on_insert<V: Into<Self::Type>>(self, value: V) -> Update<Self::Model>
+    where
+        Self::Type: Into<Bson>,
+    {
+        Update::with("$setOnInsert", Self::NAME, value.into())
+    }
+
+    /// Create `$push` update
+    fn push<V: Into<<Self::Type as Array>::Item>>(self, value: V) -> Update<Self::Model>
+    where
+        Self::Type: Array,
+        <Self::Type as Array>::Item: Into<Bson>,
+    {
+        Update::with("$push", Self::NAME, value.into())
+    }
+
+    /// Create `$push` update with `$each` modifier
+    fn push_each<T, V>(self, values: V) -> PushEach<Self>
+    where
+        Self::Type: Array,
+        <Self::Type as Array>::Item: Into<Bson>,
+        T: Into<<Self::Type as Array>::Item>,
+        V: IntoIterator<Item = T>,
+    {
+        PushEach::new(values.into_iter().map(|v| v.into().into()).collect())
+    }
+
+    /// Create `$pull` update
+    fn pull<V: Into<<Self::Type as Array>::Item>>(self, value: V) -> Update<Self::Model>
+    where
+        Self::Type: Array,
+        <Self::Type as Array>::Item: Into<Bson>,
+    {
+        Update::with("$pull", Self::NAME, value.into())
+    }
+
+    /// Create `$pullAll` update
+    fn pull_all<T, V>(self, values: V) -> Update<Self::Model>
+    where
+        Self::Type: Array,
+        <Self::Type as Array>::Item: Into<Bson>,
+        T: Into<<Self::Type as Array>::Item>,
+        V: IntoIterator<Item = T>,
+    {
+        let values = values
+            .into_iter()
+            .map(|v| v.into().into())
+            .collect::<Vec<Bson>>();
+        Update::with("$pullAll", Self::NAME, values)
+    }
+
+    /// Create `$addToSet` update
+    fn add_to_set<V: Into<<Self::Type as Array>::Item>>(self, value: V) -> Update<Self::Model>
+    where
+        Self::Type: Array,
+        <Self::Type as Array>::Item: Into<Bson>,
+    {
+        Update::with("$addToSet", Self::NAME, value.into())
+    }
+
+    /// Create `$pop` update removing the first element
+    fn pop_front(self) -> Update<Self::Model>
+    where
+        Self::Type: Array,
+    {
+        Update::with("$pop", Self::NAME, -1)
+    }
+
+    /// Create `$pop` update removing the last element
+    fn pop_back(self) -> Update<Self::Model>
+    where
+        Self::Type: Array,
+    {
+        Update::with("$pop", Self::NAME, 1)
+    }
+
+    /// Get `$` positional path of the first element matched by the filter
+    fn first_matched(self) -> Positional<Self>
+    where
+        Self::Type: Array,
+    {
+        Positional::new("$")
+    }
+
+    /// Get `$[]` positional path of all elements
+    fn all_elements(self) -> Positional<Self>
+    where
+        Self::Type: Array,
+    {
+        Positional::new("$[]")
+    }
+
+    /// Get element identifier used by `$[<identifier>]` and `arrayFilters`
+    fn elem<S: Into<String>>(self, ident: S) -> Elem<Self>
+    where
+        Self::Type: Array,
+        <Self::Type as Array>::Item: Into<Bson>,
+    {
+        Elem::new(ident)
+    }
+}
+
+impl<T> Updatable for T where T: Field {}
+
+/// FieldSet
+///
+/// Represents a set of fields of a model
+pub trait FieldSet<M: Model> {
+    /// Get the names of the fields
+    fn names(&self) -> Vec<&'static str>;
+}
+
+impl<M, F> FieldSet<M> for F
+where
+    M: Model,
+    F: Field<Model = M>,
+{
+    fn names(&self) -> Vec<&'static str> {
+        vec![F::NAME]
+    }
+}
+
+macro_rules! impl_field_set {
+    ($($f:ident),+) => {
+        impl<M, $($f),+> FieldSet<M> for ($($f,)+)
+        where
+            M: Model,
+            $($f: Field<Model = M>),+
+        {
+            fn names(&self) -> Vec<&'static str> {
+                vec![$($f::NAME),+]
+            }
+        }
+    };
+}
+
+impl_field_set!(A);
+impl_field_set!(A, B);
+impl_field_set!(A, B, C);
+impl_field_set!(A, B, C, D);
+impl_field_set!(A, B, C, D, E);
+impl_field_set!(A, B, C, D, E, F);
+impl_field_set!(A, B, C, D, E, F, G);
+impl_field_set!(A, B, C, D, E, F, G, H);
+impl_field_set!(A, B, C, D, E, F, G, H, I);
+impl_field_set!(A, B, C, D, E, F, G, H, I, J);
+impl_field_set!(A, B, C, D, E, F, G, H, I, J, K);
+impl_field_set!(A, B, C, D, E, F, G, H, I, J, K, L);
+
+/// IntoSort
+///
+/// Allows expression to be used as sort, keys are kept in order
+pub trait IntoSort {
+    /// Get the expression as sort
+    fn into_sort(self) -> Document;
+}
+
 /// SortAble
 ///
 /// Allows a certain field to be sorted
@@ -0,0 +1,203 @@
+use crate::types::{DateTime, ID};
+use bson::{doc, oid::ObjectId, Bson, Decimal128, Document};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+
+/// BsonSchema
+///
+/// Represents a type with a `$jsonSchema` description of its bson shape
+pub trait BsonSchema {
+    /// Get the schema of the type
+    fn bson_schema() -> Document;
+
+    /// Whether the field of the type must be present in the document
+    fn required() -> bool {
+        true
+    }
+}
+
+/// Build the schema of an object from its fields, given as name, schema and whether it is required
+pub fn object_schema(fields: Vec<(&str, Document, bool)>) -> Document {
+    let required = fields
+        .iter()
+        .filter(|(_, _, required)| *required)
+        .map(|(name, _, _)| Bson::String(name.to_string()))
+        .collect::<Vec<_>>();
+    let properties = fields
+        .into_iter()
+        .map(|(name, schema, _)| (name.to_string(), Bson::Document(schema)))
+        .collect::<Document>();
+    let mut schema = doc! { "bsonType": "object", "properties": properties };
+    // `required` must not be empty
+    if !required.is_empty() {
+        schema.insert("required", required);
+    }
+    schema
+}
+
+macro_rules! bson_type {
+    ($name:literal: $($ty:ty),*) => {
+        $(
+            impl BsonSchema for $ty {
+                fn bson_schema() -> Document {
+                    doc! { "bsonType": $name }
+                }
+            }
+        )*
+    };
+}
+
+// chrono dates are stored as RFC 3339 strings unless a serde helper says otherwise
+bson_type!("string": String, char, chrono::DateTime<chrono::Utc>);
+bson_type!("bool": bool);
+bson_type!("int": i8, i16, i32, u8, u16);
+bson_type!("long": i64, isize, u32, u64, usize);
+bson_type!("double": f32, f64);
+bson_type!("decimal": Decimal128);
+bson_type!("date": DateTime, bson::DateTime);
+bson_type!("objectId": ID, ObjectId);
+bson_type!("object": Document);
+
+impl BsonSchema for Bson {
+    fn bson_schema() -> Document {
+        Document::new()
+    }
+}
+
+impl<T: BsonSchema> BsonSchema for Option<T> {
+    fn bson_schema() -> Document {
+        let mut schema = T::bson_schema();
+        match schema.get_str("bsonType") {
+            Ok(ty) => {
+                let ty = ty.to_string();
+                schema.insert("bsonType", vec![ty, "null".to_string()]);
+                schema
+            }
+            Err(_) if schema.is_empty() => schema,
+            Err(_) => doc! { "anyOf": [schema, { "bsonType": "null" }] },
+        }
+    }
+
+    fn required() -> bool {
+        false
+    }
+}
+
+impl<T: BsonSchema> BsonSchema for Box<T> {
+    fn bson_schema() -> Document {
+        T::bson_schema()
+    }
+
+    fn required() -> bool {
+        T::required()
+    }
+}
+
+macro_rules! bson_array {
+    ($($ty:ident),*) => {
+        $(
+            impl<T: BsonSchema> BsonSchema for $ty<T> {
+                fn bson_schema() -> Document {
+                    doc! { "bsonType": "array", "items": T::bson_schema() }
+                }
+            }
+        )*
+    };
+}
+
+bson_array!(Vec, HashSet, BTreeSet);
+
+macro_rules! bson_map {
+    ($($ty:ident),*) => {
+        $(
+            impl<T: BsonSchema> BsonSchema for $ty<String, T> {
+                fn bson_schema() -> Document {
+                    doc! { "bsonType": "object", "additionalProperties": T::bson_schema() }
+                }
+            }
+        )*
+    };
+}
+
+bson_map!(HashMap, BTreeMap);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn option_accepts_null_and_is_not_required() {
+        assert_eq!(
+            Option::<i32>::bson_schema(),
+            doc! { "bsonType": ["int", "null"] }
+        );
+        assert!(!Option::<i32>::required());
+        assert!(i32::required());
+        assert_eq!(Option::<Bson>::bson_schema(), Document::new());
+        assert_eq!(
+            Option::<Vec<i32>>::bson_schema(),
+            doc! { "bsonType": ["array", "null"], "items": { "bsonType": "int" } }
+        );
+    }
+
+    #[test]
+    fn option_without_a_single_type_uses_any_of() {
+        struct Either;
+
+        impl BsonSchema for Either {
+            fn bson_schema() -> Document {
+                doc! { "bsonType": ["int", "string"] }
+            }
+        }
+
+        assert_eq!(
+            Option::<Either>::bson_schema(),
+            doc! { "anyOf": [{ "bsonType": ["int", "string"] }, { "bsonType": "null" }] }
+        );
+    }
+
+    #[test]
+    fn collections_describe_their_items() {
+        assert_eq!(
+            Vec::<String>::bson_schema(),
+            doc! { "bsonType": "array", "items": { "bsonType": "string" } }
+        );
+        assert_eq!(
+            BTreeSet::<ID>::bson_schema(),
+            doc! { "bsonType": "array", "items": { "bsonType": "objectId" } }
+        );
+        assert_eq!(
+            HashMap::<String, i64>::bson_schema(),
+            doc! { "bsonType": "object", "additionalProperties": { "bsonType": "long" } }
+        );
+        assert_eq!(
+            chrono::DateTime::<chrono::Utc>::bson_schema(),
+            doc! { "bsonType": "string" }
+        );
+        assert!(!Box::<Option<bool>>::required());
+    }
+
+    #[test]
+    fn object_schema_lists_the_required_fields() {
+        assert_eq!(
+            object_schema(vec![
+                ("name", String::bson_schema(), true),
+                ("age", Option::<i32>::bson_schema(), false),
+            ]),
+            doc! {
+                "bsonType": "object",
+                "properties": {
+                    "name": { "bsonType": "string" },
+                    "age": { "bsonType": ["int", "null"] },
+                },
+                "required": ["name"],
+            }
+        );
+        assert_eq!(
+            object_schema(vec![("age", Option::<i32>::bson_schema(), false)]),
+            doc! {
+                "bsonType": "object",
+                "properties": { "age": { "bsonType": ["int", "null"] } },
+            }
+        );
+    }
+}
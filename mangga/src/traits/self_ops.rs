@@ -8,7 +8,7 @@ use serde::Serialize;
 /// Allows to insert current model
 pub trait Insertable: Model + Serialize {
     /// Insert current model
-    fn insert(&self) -> InsertOne<Self> {
+    fn insert(&self) -> InsertOne<'_, Self> {
         InsertOne::new(self)
     }
 }
@@ -0,0 +1,119 @@
+use crate::{
+    db::{get_connection, DEFAULT_CONNECTION},
+    Error,
+    Result,
+};
+use futures::lock::{Mutex, MutexGuard};
+use mongodb::{
+    error::{TRANSIENT_TRANSACTION_ERROR, UNKNOWN_TRANSACTION_COMMIT_RESULT},
+    ClientSession,
+};
+use std::{
+    future::Future,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+/// Maximum time spent retrying the transaction
+const MAX_RETRY_TIME: Duration = Duration::from_secs(120);
+
+/// Transaction
+///
+/// Represents a handle of the client session running the transaction
+///
+/// Operations receive the handle with `.session(&mut tx)` and run one at a time on the session
+#[derive(Clone)]
+pub struct Transaction(Arc<Mutex<ClientSession>>);
+
+impl Transaction {
+    /// Lock the underlying session
+    pub(crate) async fn lock(&self) -> MutexGuard<'_, ClientSession> {
+        self.0.lock().await
+    }
+}
+
+/// Check if the error has the given label
+fn has_label(error: &Error, label: &str) -> bool {
+    match error {
+        Error::MongoDB(e) => e.contains_label(label),
+        _ => false,
+    }
+}
+
+/// Run the closure inside a transaction of the default connection
+///
+/// The transaction is committed when the closure succeeds and aborted otherwise.
+/// The whole transaction is retried on `TransientTransactionError` and the commit
+/// is retried on `UnknownTransactionCommitResult`, so the closure may run more than once.
+///
+/// # Examples
+///
+/// ```no_run
+/// use mangga::prelude::*;
+///
+/// # async fn run() -> mangga::Result<()> {
+/// mangga::transaction(|mut tx| async move {
+///     // operations passed `.session(&mut tx)` are part of the transaction
+///     Ok(())
+/// })
+/// .await?;
+/// # Ok(())
+/// # }
+/// ```
+pub async fn transaction<F, Fut, R>(f: F) -> Result<R>
+where
+    F: FnMut(Transaction) -> Fut,
+    Fut: Future<Output = Result<R>>,
+{
+    let mangga = get_connection(DEFAULT_CONNECTION)?;
+    run(mangga.client(), f).await
+}
+
+/// Run the closure inside a transaction of the client
+pub(crate) async fn run<F, Fut, R>(client: &mongodb::Client, mut f: F) -> Result<R>
+where
+    F: FnMut(Transaction) -> Fut,
+    Fut: Future<Output = Result<R>>,
+{
+    let session = client.start_session().await?;
+    let tx = Transaction(Arc::new(Mutex::new(session)));
+    let start = Instant::now();
+
+    'attempt: loop {
+        tx.lock().await.start_transaction().await?;
+
+        let value = match f(tx.clone()).await {
+            Ok(value) => value,
+            Err(e) => {
+                // the transaction may already be aborted by the server
+                let _ = tx.lock().await.abort_transaction().await;
+                if has_label(&e, TRANSIENT_TRANSACTION_ERROR) && start.elapsed() < MAX_RETRY_TIME {
+                    continue 'attempt;
+                }
+                return Err(e);
+            }
+        };
+
+        loop {
+            let res = tx
+                .lock()
+                .await
+                .commit_transaction()
+                .await
+                .map_err(Error::from);
+            match res {
+                Ok(()) => return Ok(value),
+                Err(e) if start.elapsed() < MAX_RETRY_TIME => {
+                    if has_label(&e, UNKNOWN_TRANSACTION_COMMIT_RESULT) {
+                        continue;
+                    }
+                    if has_label(&e, TRANSIENT_TRANSACTION_ERROR) {
+                        continue 'attempt;
+                    }
+                    return Err(e);
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+}
@@ -0,0 +1,323 @@
+use crate::traits::BsonDate;
+use mongodb::options::{
+    ClusteredIndex,
+    CreateCollectionOptions,
+    TimeseriesGranularity,
+    TimeseriesOptions,
+    ValidationAction,
+    ValidationLevel,
+};
+use std::time::Duration;
+
+/// CappedSpec
+///
+/// Represents the size limits of a capped collection
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CappedSpec {
+    /// Maximum size in bytes
+    pub size: u64,
+    /// Maximum number of documents
+    pub max: Option<u64>,
+}
+
+/// TimeseriesSpec
+///
+/// Represents the options of a time-series collection
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimeseriesSpec {
+    /// Field holding the date of the measurement
+    pub time_field: &'static str,
+    /// Field holding the metadata of the measurement
+    pub meta_field: Option<&'static str>,
+    /// Granularity of the measurements, `seconds`, `minutes` or `hours`
+    pub granularity: Option<&'static str>,
+}
+
+impl TimeseriesSpec {
+    /// Time-series options of the date field with every other option unset
+    pub const fn new<T: BsonDate>(time_field: &'static str) -> Self {
+        Self {
+            time_field,
+            meta_field: None,
+            granularity: None,
+        }
+    }
+
+    /// Get the granularity as understood by the driver, the server defaults to seconds
+    fn granularity(&self) -> TimeseriesGranularity {
+        match self.granularity {
+            Some("minutes") => TimeseriesGranularity::Minutes,
+            Some("hours") => TimeseriesGranularity::Hours,
+            _ => TimeseriesGranularity::Seconds,
+        }
+    }
+}
+
+/// CollectionSpec
+///
+/// Represents the options of the collection declared in the model
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CollectionSpec {
+    /// Size limits of a capped collection
+    pub capped: Option<CappedSpec>,
+    /// Options of a time-series collection
+    pub timeseries: Option<TimeseriesSpec>,
+    /// Whether the collection is clustered by `_id`
+    pub clustered: bool,
+    /// Seconds after which the documents expire
+    pub expire_after: Option<u64>,
+    /// How strictly the schema is applied, `off`, `strict` or `moderate`
+    pub validation_level: Option<&'static str>,
+    /// What happens to invalid documents, `error` or `warn`
+    pub validation_action: Option<&'static str>,
+}
+
+impl CollectionSpec {
+    /// Collection spec with every option unset
+    pub const DEFAULT: CollectionSpec = CollectionSpec {
+        capped: None,
+        timeseries: None,
+        clustered: false,
+        expire_after: None,
+        validation_level: None,
+        validation_action: None,
+    };
+
+    /// Check whether the spec declares capped, time-series, clustered or expiry options
+    pub fn has_options(&self) -> bool {
+        self.capped.is_some()
+            || self.timeseries.is_some()
+            || self.clustered
+            || self.expire_after.is_some()
+    }
+
+    /// Get the options to create the collection with
+    pub fn options(&self) -> CreateCollectionOptions {
+        let mut opts = CreateCollectionOptions::default();
+        if let Some(capped) = self.capped {
+            opts.capped = Some(true);
+            opts.size = Some(capped.size);
+            opts.max = capped.max;
+        }
+        opts.timeseries = self.timeseries.map(|timeseries| {
+            TimeseriesOptions::builder()
+                .time_field(timeseries.time_field)
+                .meta_field(timeseries.meta_field.map(ToString::to_string))
+                .granularity(timeseries.granularity.map(|_| timeseries.granularity()))
+                .build()
+        });
+        if self.clustered {
+            opts.clustered_index = Some(ClusteredIndex::default());
+        }
+        opts.expire_after_seconds = self.expire_after.map(Duration::from_secs);
+        opts.validation_level = self.validation_level();
+        opts.validation_action = self.validation_action();
+        opts
+    }
+
+    /// Get the validation level as understood by the driver
+    pub fn validation_level(&self) -> Option<ValidationLevel> {
+        self.validation_level.and_then(|level| match level {
+            "off" => Some(ValidationLevel::Off),
+            "strict" => Some(ValidationLevel::Strict),
+            "moderate" => Some(ValidationLevel::Moderate),
+            _ => None,
+        })
+    }
+
+    /// Get the validation action as understood by the driver
+    pub fn validation_action(&self) -> Option<ValidationAction> {
+        self.validation_action.and_then(|action| match action {
+            "error" => Some(ValidationAction::Error),
+            "warn" => Some(ValidationAction::Warn),
+            _ => None,
+        })
+    }
+
+    /// Get the differences between the spec and the options of an existing collection
+    pub fn mismatches(&self, existing: &CreateCollectionOptions) -> Vec<String> {
+        let mut mismatches = vec![];
+        let mut check = |name: &str, expected: String, found: String| {
+            if expected != found {
+                mismatches.push(format!("{} expected {}, found {}", name, expected, found));
+            }
+        };
+
+        let capped = existing.capped.unwrap_or(false);
+        match self.capped {
+            Some(spec) if capped => {
+                // The server rounds the size up to 4096 bytes or to a multiple of 256
+                let rounded = spec.size.max(4096).div_ceil(256) * 256;
+                let size = existing.size.unwrap_or_default();
+                if size != spec.size && size != rounded {
+                    check("size", spec.size.to_string(), size.to_string());
+                }
+                check(
+                    "max",
+                    format!("{:?}", spec.max),
+                    format!("{:?}", existing.max),
+                );
+            }
+            spec => check("capped", spec.is_some().to_string(), capped.to_string()),
+        }
+
+        match (self.timeseries, &existing.timeseries) {
+            (Some(spec), Some(timeseries)) => {
+                check(
+                    "time_field",
+                    spec.time_field.to_string(),
+                    timeseries.time_field.clone(),
+                );
+                check(
+                    "meta_field",
+                    format!("{:?}", spec.meta_field),
+                    format!("{:?}", timeseries.meta_field.as_deref()),
+                );
+                let granularity = timeseries
+                    .granularity
+                    .clone()
+                    .unwrap_or(TimeseriesGranularity::Seconds);
+                check(
+                    "granularity",
+                    format!("{:?}", spec.granularity()),
+                    format!("{:?}", granularity),
+                );
+            }
+            (spec, timeseries) => check(
+                "timeseries",
+                spec.is_some().to_string(),
+                timeseries.is_some().to_string(),
+            ),
+        }
+
+        // Time-series collections may report an implicit clustered index
+        if self.timeseries.is_none() {
+            check(
+                "clustered",
+                self.clustered.to_string(),
+                existing.clustered_index.is_some().to_string(),
+            );
+        }
+        check(
+            "expire_after",
+            format!("{:?}", self.expire_after),
+            format!(
+                "{:?}",
+                existing.expire_after_seconds.map(|exp| exp.as_secs())
+            ),
+        );
+
+        mismatches
+    }
+}
+
+impl Default for CollectionSpec {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::DateTime;
+
+    fn capped(size: u64, max: Option<u64>) -> CreateCollectionOptions {
+        let mut opts = CreateCollectionOptions::default();
+        opts.capped = Some(true);
+        opts.size = Some(size);
+        opts.max = max;
+        opts
+    }
+
+    #[test]
+    fn mismatches_accept_the_size_rounded_by_the_server() {
+        let spec = CollectionSpec {
+            capped: Some(CappedSpec {
+                size: 1000,
+                max: Some(10),
+            }),
+            ..CollectionSpec::DEFAULT
+        };
+        assert!(spec.mismatches(&capped(1000, Some(10))).is_empty());
+        assert!(spec.mismatches(&capped(4096, Some(10))).is_empty());
+        assert_eq!(
+            spec.mismatches(&capped(8192, Some(10))),
+            vec!["size expected 1000, found 8192"]
+        );
+
+        let spec = CollectionSpec {
+            capped: Some(CappedSpec {
+                size: 5000,
+                max: None,
+            }),
+            ..CollectionSpec::DEFAULT
+        };
+        assert!(spec.mismatches(&capped(5120, None)).is_empty());
+        assert_eq!(
+            spec.mismatches(&capped(5120, Some(10))),
+            vec!["max expected None, found Some(10)"]
+        );
+    }
+
+    #[test]
+    fn mismatches_report_every_differing_option() {
+        let spec = CollectionSpec {
+            clustered: true,
+            expire_after: Some(60),
+            ..CollectionSpec::DEFAULT
+        };
+        assert_eq!(
+            spec.mismatches(&capped(4096, None)),
+            vec![
+                "capped expected false, found true",
+                "clustered expected true, found false",
+                "expire_after expected Some(60), found None",
+            ]
+        );
+        assert!(spec.mismatches(&spec.options()).is_empty());
+        assert!(CollectionSpec::DEFAULT
+            .mismatches(&CreateCollectionOptions::default())
+            .is_empty());
+    }
+
+    #[test]
+    fn mismatches_compare_time_series_options() {
+        let spec = CollectionSpec {
+            timeseries: Some(TimeseriesSpec {
+                meta_field: Some("sensor"),
+                ..TimeseriesSpec::new::<DateTime>("at")
+            }),
+            ..CollectionSpec::DEFAULT
+        };
+        let mut opts = spec.options();
+        // the server reports the default granularity and an implicit clustered index
+        if let Some(timeseries) = opts.timeseries.as_mut() {
+            timeseries.granularity = Some(TimeseriesGranularity::Seconds);
+        }
+        opts.clustered_index = Some(ClusteredIndex::default());
+        assert!(spec.mismatches(&opts).is_empty());
+
+        if let Some(timeseries) = opts.timeseries.as_mut() {
+            timeseries.granularity = Some(TimeseriesGranularity::Hours);
+        }
+        assert_eq!(
+            spec.mismatches(&opts),
+            vec!["granularity expected Seconds, found Hours"]
+        );
+        assert_eq!(
+            spec.mismatches(&CreateCollectionOptions::default()),
+            vec!["timeseries expected true, found false"]
+        );
+    }
+
+    #[test]
+    fn has_options_of_declared_options_only() {
+        assert!(!CollectionSpec::DEFAULT.has_options());
+        let spec = CollectionSpec {
+            expire_after: Some(60),
+            ..CollectionSpec::DEFAULT
+        };
+        assert!(spec.has_options());
+    }
+}
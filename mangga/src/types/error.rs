@@ -12,6 +12,15 @@ pub enum Error {
     /// Init error
     #[error("Init error: {0}")]
     Init(String),
+    /// Invalid pagination cursor error
+    #[error("Invalid cursor: {0}")]
+    Cursor(String),
+    /// Invalid page error
+    #[error("Invalid page: {0}")]
+    Page(String),
+    /// Existing collection created with different options error
+    #[error("Collection options mismatch: {0}")]
+    CollectionMismatch(String),
 }
 
 impl Error {
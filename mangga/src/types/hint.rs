@@ -0,0 +1,47 @@
+use mongodb::options::Hint;
+use std::marker::PhantomData;
+
+/// ModelIndex
+///
+/// Represents an index of the model used as query hint
+///
+/// Every index declared in the model has an `INDEX_*` constant on the model
+#[derive(Debug)]
+pub struct ModelIndex<M> {
+    name: &'static str,
+    __marker: PhantomData<M>,
+}
+
+impl<M> ModelIndex<M> {
+    /// Index of the model by name, for indexes not declared in the model
+    pub const fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            __marker: PhantomData,
+        }
+    }
+
+    /// Index of the `_id` field created by the server
+    pub const fn id() -> Self {
+        Self::new("_id_")
+    }
+
+    /// Get the name of the index
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+}
+
+impl<M> Clone for ModelIndex<M> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<M> Copy for ModelIndex<M> {}
+
+impl<M> From<ModelIndex<M>> for Hint {
+    fn from(value: ModelIndex<M>) -> Self {
+        Hint::Name(value.name.to_string())
+    }
+}
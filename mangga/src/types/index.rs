@@ -0,0 +1,540 @@
+use bson::{Bson, Document};
+use mongodb::{
+    options::{Collation, CollationStrength, IndexOptions},
+    IndexModel,
+};
+use std::time::Duration;
+
+/// IndexKey
+///
+/// Represents the kind of an index key
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IndexKey {
+    /// Ascending key
+    Asc,
+    /// Descending key
+    Desc,
+    /// Text key
+    Text,
+    /// Hashed key
+    Hashed,
+    /// Geospatial key on a sphere
+    Sphere2d,
+}
+
+impl From<IndexKey> for Bson {
+    fn from(value: IndexKey) -> Self {
+        match value {
+            IndexKey::Asc => Bson::Int32(1),
+            IndexKey::Desc => Bson::Int32(-1),
+            IndexKey::Text => Bson::String("text".to_string()),
+            IndexKey::Hashed => Bson::String("hashed".to_string()),
+            IndexKey::Sphere2d => Bson::String("2dsphere".to_string()),
+        }
+    }
+}
+
+/// IndexCollation
+///
+/// Represents the collation of an index
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IndexCollation {
+    /// Locale of the collation
+    pub locale: &'static str,
+    /// Level of comparison, from 1 to 5
+    pub strength: Option<u32>,
+    /// Whether to include case comparison at strength 1 or 2
+    pub case_level: Option<bool>,
+    /// Sort order of case differences, `upper`, `lower` or `off`
+    pub case_first: Option<&'static str>,
+    /// Whether to compare numeric strings as numbers
+    pub numeric_ordering: Option<bool>,
+    /// Whether whitespace and punctuation are considered, `non-ignorable` or `shifted`
+    pub alternate: Option<&'static str>,
+    /// Characters ignored with the shifted alternate, `punct` or `space`
+    pub max_variable: Option<&'static str>,
+    /// Whether to check if text requires normalization
+    pub normalization: Option<bool>,
+    /// Whether strings with diacritics sort from the back of the string
+    pub backwards: Option<bool>,
+}
+
+impl IndexCollation {
+    /// Collation of the locale with every option unset
+    pub const fn new(locale: &'static str) -> Self {
+        Self {
+            locale,
+            strength: None,
+            case_level: None,
+            case_first: None,
+            numeric_ordering: None,
+            alternate: None,
+            max_variable: None,
+            normalization: None,
+            backwards: None,
+        }
+    }
+}
+
+impl From<IndexCollation> for Collation {
+    fn from(value: IndexCollation) -> Self {
+        let mut collation = Collation::default();
+        collation.locale = value.locale.to_string();
+        collation.strength = value
+            .strength
+            .and_then(|s| CollationStrength::try_from(s).ok());
+        collation.case_level = value.case_level;
+        collation.case_first = value.case_first.and_then(|c| c.parse().ok());
+        collation.numeric_ordering = value.numeric_ordering;
+        collation.alternate = value.alternate.and_then(|a| a.parse().ok());
+        collation.max_variable = value.max_variable.and_then(|m| m.parse().ok());
+        collation.normalization = value.normalization;
+        collation.backwards = value.backwards;
+        collation
+    }
+}
+
+/// IndexSpec
+///
+/// Represents an index declared in the model
+///
+/// Wildcard indexes use `$**` or `field.$**` as the key name
+#[derive(Debug, Clone, Copy)]
+pub struct IndexSpec {
+    /// Name of the index
+    pub name: &'static str,
+    /// Keys of the index in order
+    pub keys: &'static [(&'static str, IndexKey)],
+    /// Whether the index is unique
+    pub unique: bool,
+    /// Seconds after which the documents expire
+    pub expire_after: Option<u64>,
+    /// Weights of the text index fields
+    pub weights: &'static [(&'static str, i32)],
+    /// Default language of the text index
+    pub default_language: Option<&'static str>,
+    /// Only index the documents matching the filter
+    pub partial: Option<fn() -> Document>,
+    /// Only index the documents having the fields
+    pub sparse: bool,
+    /// Hide the index from the query planner
+    pub hidden: bool,
+    /// Collation of the index
+    pub collation: Option<IndexCollation>,
+}
+
+impl IndexSpec {
+    /// Index spec with every option unset
+    pub const DEFAULT: IndexSpec = IndexSpec {
+        name: "",
+        keys: &[],
+        unique: false,
+        expire_after: None,
+        weights: &[],
+        default_language: None,
+        partial: None,
+        sparse: false,
+        hidden: false,
+        collation: None,
+    };
+
+    /// Get the keys document
+    pub fn keys(&self) -> Document {
+        self.keys
+            .iter()
+            .map(|(name, key)| (name.to_string(), Bson::from(*key)))
+            .collect()
+    }
+
+    /// Get the index options
+    pub fn options(&self) -> IndexOptions {
+        let mut opts = IndexOptions::default();
+        opts.name = Some(self.name.to_string());
+        if self.unique {
+            opts.unique = Some(true);
+        }
+        opts.expire_after = self.expire_after.map(Duration::from_secs);
+        if !self.weights.is_empty() {
+            opts.weights = Some(
+                self.weights
+                    .iter()
+                    .map(|(name, weight)| (name.to_string(), Bson::Int32(*weight)))
+                    .collect(),
+            );
+        }
+        opts.default_language = self.default_language.map(ToString::to_string);
+        opts.partial_filter_expression = self.partial.map(|filter| filter());
+        if self.sparse {
+            opts.sparse = Some(true);
+        }
+        if self.hidden {
+            opts.hidden = Some(true);
+        }
+        opts.collation = self.collation.map(Collation::from);
+        opts
+    }
+
+    /// Get the index model
+    pub fn model(&self) -> IndexModel {
+        IndexModel::builder()
+            .keys(self.keys())
+            .options(self.options())
+            .build()
+    }
+
+    /// Check whether an existing index matches the keys and options of the spec
+    pub fn matches(&self, index: &IndexModel) -> bool {
+        let default = IndexOptions::default();
+        let opts = index.options.as_ref().unwrap_or(&default);
+        // documents compare regardless of key order, which matters for compound indexes
+        let keys = |keys: &Document| {
+            keys.iter()
+                .map(|(name, key)| (name.clone(), normalize(key)))
+                .collect::<Vec<_>>()
+        };
+        if keys(&self.server_keys()) != keys(&index.keys) {
+            return false;
+        }
+        if self.unique != opts.unique.unwrap_or(false)
+            || self.sparse != opts.sparse.unwrap_or(false)
+            || self.hidden != opts.hidden.unwrap_or(false)
+            || self.expire_after != opts.expire_after.map(|exp| exp.as_secs())
+        {
+            return false;
+        }
+        let partial = self
+            .partial
+            .map(|filter| normalize(&Bson::Document(filter())));
+        if partial
+            != opts
+                .partial_filter_expression
+                .clone()
+                .map(|filter| normalize(&Bson::Document(filter)))
+        {
+            return false;
+        }
+        if self.is_text() {
+            let weights = self
+                .keys
+                .iter()
+                .filter(|(_, key)| *key == IndexKey::Text)
+                .map(|(name, _)| {
+                    let weight = self
+                        .weights
+                        .iter()
+                        .find(|(field, _)| field == name)
+                        .map_or(1, |(_, weight)| *weight);
+                    (name.to_string(), Bson::Int32(weight))
+                })
+                .collect::<Document>();
+            let existing = opts.weights.clone().unwrap_or_default();
+            if normalize(&Bson::Document(weights)) != normalize(&Bson::Document(existing)) {
+                return false;
+            }
+            let language = |lang: Option<&str>| lang.unwrap_or("english").to_string();
+            if language(self.default_language) != language(opts.default_language.as_deref()) {
+                return false;
+            }
+        }
+        match (self.collation, &opts.collation) {
+            (None, None) => true,
+            (Some(collation), Some(existing)) => {
+                // The server fills in every collation option, only the declared ones are compared
+                let local = bson::to_document(&Collation::from(collation)).unwrap_or_default();
+                let existing = bson::to_document(existing).unwrap_or_default();
+                local
+                    .iter()
+                    .all(|(key, value)| existing.get(key).map(normalize) == Some(normalize(value)))
+            }
+            _ => false,
+        }
+    }
+
+    /// Check whether the index has text keys
+    fn is_text(&self) -> bool {
+        self.keys.iter().any(|(_, key)| *key == IndexKey::Text)
+    }
+
+    /// Get the keys document as reported by the server
+    ///
+    /// Text keys are stored as `_fts` and `_ftsx`, with the fields moved to the weights
+    fn server_keys(&self) -> Document {
+        let mut keys = Document::new();
+        for (name, key) in self.keys {
+            if *key != IndexKey::Text {
+                keys.insert(name.to_string(), Bson::from(*key));
+            } else if !keys.contains_key("_fts") {
+                keys.insert("_fts", "text");
+                keys.insert("_ftsx", 1);
+            }
+        }
+        keys
+    }
+}
+
+/// Normalize numbers to doubles so `1`, `1i64` and `1.0` compare equal
+fn normalize(value: &Bson) -> Bson {
+    match value {
+        Bson::Int32(n) => Bson::Double(*n as f64),
+        Bson::Int64(n) => Bson::Double(*n as f64),
+        Bson::Document(doc) => Bson::Document(
+            doc.iter()
+                .map(|(key, value)| (key.clone(), normalize(value)))
+                .collect(),
+        ),
+        Bson::Array(arr) => Bson::Array(arr.iter().map(normalize).collect()),
+        value => value.clone(),
+    }
+}
+
+/// IndexPolicy
+///
+/// Represents how the model setup treats the indexes of the collection
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IndexPolicy {
+    /// Drop the indexes not declared in the model
+    pub drop_unknown: bool,
+    /// Only plan the changes without executing them
+    pub dry_run: bool,
+}
+
+impl IndexPolicy {
+    /// Drop unknown indexes and execute the plan
+    pub const DEFAULT: IndexPolicy = IndexPolicy {
+        drop_unknown: true,
+        dry_run: false,
+    };
+}
+
+impl Default for IndexPolicy {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+/// IndexPlan
+///
+/// Represents the index changes needed to bring a collection in line with the model
+///
+/// Changed indexes are both dropped and created
+#[derive(Debug, Clone, Default)]
+pub struct IndexPlan {
+    /// Indexes to create
+    pub create: Vec<IndexSpec>,
+    /// Names of the indexes to drop
+    pub drop: Vec<String>,
+    /// Names of the indexes left as they are
+    pub keep: Vec<String>,
+    /// Names of the indexes rebuilt because their keys or options changed
+    pub changed: Vec<String>,
+    /// Whether the plan was executed
+    pub executed: bool,
+}
+
+impl IndexPlan {
+    /// Diff the declared indexes against the existing ones
+    pub fn new(specs: &[IndexSpec], existing: &[IndexModel], policy: IndexPolicy) -> Self {
+        let mut plan = IndexPlan::default();
+        for index in existing {
+            let Some(name) = index.options.as_ref().and_then(|opts| opts.name.clone()) else {
+                continue;
+            };
+            if name == "_id_" {
+                continue;
+            }
+            match specs.iter().find(|spec| spec.name == name) {
+                Some(spec) if spec.matches(index) => plan.keep.push(name),
+                Some(_) => {
+                    plan.drop.push(name.clone());
+                    plan.changed.push(name);
+                }
+                None if policy.drop_unknown => plan.drop.push(name),
+                None => plan.keep.push(name),
+            }
+        }
+        plan.create = specs
+            .iter()
+            .filter(|spec| !plan.keep.iter().any(|name| name == spec.name))
+            .copied()
+            .collect();
+        plan
+    }
+
+    /// Check whether the plan has nothing to do
+    pub fn is_empty(&self) -> bool {
+        self.create.is_empty() && self.drop.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bson::doc;
+
+    /// Documents compare regardless of key order, the order of index keys matters
+    fn keys(keys: Document) -> Vec<(String, Bson)> {
+        keys.into_iter().collect()
+    }
+
+    #[test]
+    fn keys_keep_the_declared_order() {
+        let spec = IndexSpec {
+            name: "age_email",
+            keys: &[("age", IndexKey::Desc), ("email", IndexKey::Asc)],
+            ..IndexSpec::DEFAULT
+        };
+        assert_eq!(keys(spec.keys()), keys(doc! {"age": -1, "email": 1}));
+        assert_ne!(keys(spec.keys()), keys(doc! {"email": 1, "age": -1}));
+        assert_eq!(keys(spec.model().keys), keys(doc! {"age": -1, "email": 1}));
+    }
+
+    #[test]
+    fn options_of_the_spec() {
+        let spec = IndexSpec {
+            name: "search",
+            keys: &[("title", IndexKey::Text), ("body", IndexKey::Text)],
+            weights: &[("title", 10)],
+            default_language: Some("indonesian"),
+            ..IndexSpec::DEFAULT
+        };
+        let opts = spec.options();
+        assert_eq!(opts.name.as_deref(), Some("search"));
+        assert_eq!(opts.unique, None);
+        assert_eq!(opts.weights, Some(doc! {"title": 10}));
+        assert_eq!(opts.default_language.as_deref(), Some("indonesian"));
+        assert_eq!(
+            keys(spec.keys()),
+            keys(doc! {"title": "text", "body": "text"})
+        );
+
+        let spec = IndexSpec {
+            name: "session",
+            keys: &[("last_seen", IndexKey::Asc)],
+            unique: true,
+            expire_after: Some(60),
+            ..IndexSpec::DEFAULT
+        };
+        let opts = spec.options();
+        assert_eq!(opts.unique, Some(true));
+        assert_eq!(opts.expire_after, Some(Duration::from_secs(60)));
+    }
+
+    fn existing(keys: Document, opts: IndexOptions) -> IndexModel {
+        IndexModel::builder().keys(keys).options(opts).build()
+    }
+
+    fn named(name: &str) -> IndexOptions {
+        IndexOptions::builder().name(name.to_string()).build()
+    }
+
+    const EMAIL: IndexSpec = IndexSpec {
+        name: "email",
+        keys: &[("email", IndexKey::Asc), ("age", IndexKey::Desc)],
+        unique: true,
+        ..IndexSpec::DEFAULT
+    };
+
+    #[test]
+    fn matches_keys_of_any_number_type() {
+        let mut opts = named("email");
+        opts.unique = Some(true);
+        let index = existing(doc! {"email": 1.0, "age": -1_i64}, opts.clone());
+        assert!(EMAIL.matches(&index));
+
+        let index = existing(doc! {"age": -1, "email": 1}, opts.clone());
+        assert!(!EMAIL.matches(&index), "key order matters");
+
+        opts.unique = None;
+        let index = existing(doc! {"email": 1, "age": -1}, opts);
+        assert!(!EMAIL.matches(&index));
+    }
+
+    #[test]
+    fn matches_text_index_as_reported_by_the_server() {
+        let spec = IndexSpec {
+            name: "search",
+            keys: &[("title", IndexKey::Text), ("body", IndexKey::Text)],
+            weights: &[("title", 10)],
+            ..IndexSpec::DEFAULT
+        };
+        let mut opts = named("search");
+        opts.weights = Some(doc! {"title": 10_i64, "body": 1.0});
+        opts.default_language = Some("english".to_string());
+        let index = existing(doc! {"_fts": "text", "_ftsx": 1}, opts.clone());
+        assert!(spec.matches(&index));
+
+        opts.weights = Some(doc! {"title": 5, "body": 1});
+        let index = existing(doc! {"_fts": "text", "_ftsx": 1}, opts);
+        assert!(!spec.matches(&index));
+    }
+
+    #[test]
+    fn matches_normalized_partial_filter() {
+        fn partial() -> Document {
+            doc! {"age": {"$gt": 18}}
+        }
+        let spec = IndexSpec {
+            name: "adults",
+            keys: &[("age", IndexKey::Asc)],
+            partial: Some(partial),
+            ..IndexSpec::DEFAULT
+        };
+        let mut opts = named("adults");
+        opts.partial_filter_expression = Some(doc! {"age": {"$gt": 18.0}});
+        assert!(spec.matches(&existing(doc! {"age": 1}, opts.clone())));
+
+        opts.partial_filter_expression = Some(doc! {"age": {"$gt": 21}});
+        assert!(!spec.matches(&existing(doc! {"age": 1}, opts)));
+    }
+
+    #[test]
+    fn matches_declared_collation_options_only() {
+        let spec = IndexSpec {
+            name: "name",
+            keys: &[("name", IndexKey::Asc)],
+            collation: Some(IndexCollation {
+                strength: Some(2),
+                ..IndexCollation::new("en")
+            }),
+            ..IndexSpec::DEFAULT
+        };
+        let mut opts = named("name");
+        opts.collation = Some(
+            Collation::builder()
+                .locale("en")
+                .strength(CollationStrength::Secondary)
+                .case_level(false)
+                .numeric_ordering(false)
+                .build(),
+        );
+        assert!(spec.matches(&existing(doc! {"name": 1}, opts.clone())));
+
+        opts.collation = None;
+        assert!(!spec.matches(&existing(doc! {"name": 1}, opts)));
+    }
+
+    #[test]
+    fn plan_keeps_rebuilds_and_drops_indexes() {
+        let mut opts = named("email");
+        opts.unique = Some(true);
+        let indexes = [
+            existing(doc! {"_id": 1}, named("_id_")),
+            existing(doc! {"email": 1, "age": -1}, named("email")),
+            existing(doc! {"legacy": 1}, named("legacy")),
+        ];
+
+        let plan = IndexPlan::new(&[EMAIL], &indexes, IndexPolicy::DEFAULT);
+        assert_eq!(plan.changed, vec!["email"]);
+        assert_eq!(plan.drop, vec!["email", "legacy"]);
+        assert_eq!(plan.create.len(), 1);
+
+        let indexes = [existing(doc! {"email": 1, "age": -1}, opts)];
+        let policy = IndexPolicy {
+            drop_unknown: false,
+            ..IndexPolicy::DEFAULT
+        };
+        let plan = IndexPlan::new(&[EMAIL], &indexes, policy);
+        assert!(plan.is_empty());
+        assert_eq!(plan.keep, vec!["email"]);
+    }
+}
@@ -1,13 +1,26 @@
+pub use collection::{CappedSpec, CollectionSpec, TimeseriesSpec};
 pub use error::*;
 pub use id::{is_id, ID};
 pub use datetime::DateTime;
+pub use hint::ModelIndex;
+pub use index::{IndexCollation, IndexKey, IndexPlan, IndexPolicy, IndexSpec};
+pub use results::*;
 use std::pin::Pin;
 
+mod collection;
 mod error;
 mod id;
 mod datetime;
+mod hint;
+mod index;
+mod results;
 
 /// BoxFut
 ///
 /// Type alias for futures
 pub(crate) type BoxFut<T> = Pin<Box<dyn std::future::Future<Output = Result<T>> + Send>>;
+
+/// BoxStream
+///
+/// Type alias for streams
+pub(crate) type BoxStream<T> = Pin<Box<dyn futures::Stream<Item = Result<T>> + Send>>;
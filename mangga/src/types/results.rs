@@ -0,0 +1,129 @@
+use super::{Error, Result, ID};
+use bson::Bson;
+
+/// Get the document id as `ID` when it is an object id
+pub(crate) fn as_id(value: &Bson) -> Option<ID> {
+    match value {
+        Bson::ObjectId(id) => Some((*id).into()),
+        _ => None,
+    }
+}
+
+/// InsertOneResult
+///
+/// Represents the result of the insert one operation
+///
+/// Ids are kept as they are stored, they are not always object ids
+#[derive(Debug, Clone, PartialEq)]
+pub struct InsertOneResult {
+    /// Id of the inserted document
+    pub inserted_id: Bson,
+}
+
+impl InsertOneResult {
+    /// Get the inserted id as `ID`, `None` when it is not an object id
+    pub fn id(&self) -> Option<ID> {
+        as_id(&self.inserted_id)
+    }
+}
+
+impl From<mongodb::results::InsertOneResult> for InsertOneResult {
+    fn from(value: mongodb::results::InsertOneResult) -> Self {
+        Self {
+            inserted_id: value.inserted_id,
+        }
+    }
+}
+
+/// InsertManyResult
+///
+/// Represents the result of the insert many operation
+#[derive(Debug, Clone, PartialEq)]
+pub struct InsertManyResult {
+    /// Ids of the inserted documents in the order of the input
+    pub inserted_ids: Vec<Bson>,
+}
+
+impl InsertManyResult {
+    /// Get the inserted ids as `ID`, `None` when any of them is not an object id
+    pub fn ids(&self) -> Option<Vec<ID>> {
+        self.inserted_ids.iter().map(as_id).collect()
+    }
+}
+
+impl From<mongodb::results::InsertManyResult> for InsertManyResult {
+    fn from(value: mongodb::results::InsertManyResult) -> Self {
+        let mut ids = value.inserted_ids.into_iter().collect::<Vec<_>>();
+        ids.sort_by_key(|(index, _)| *index);
+        let inserted_ids = ids.into_iter().map(|(_, id)| id).collect();
+
+        Self { inserted_ids }
+    }
+}
+
+/// UpdateResult
+///
+/// Represents the result of the update operations
+#[derive(Debug, Clone, PartialEq)]
+pub struct UpdateResult {
+    /// Number of documents matched by the filter
+    pub matched_count: u64,
+    /// Number of documents modified
+    pub modified_count: u64,
+    /// Id of the upserted document
+    pub upserted_id: Option<Bson>,
+}
+
+impl UpdateResult {
+    /// Return `Error::NotFound` when no document is matched or upserted
+    pub fn expect_one(self) -> Result<Self> {
+        if self.matched_count == 0 && self.upserted_id.is_none() {
+            Err(Error::NotFound)
+        } else {
+            Ok(self)
+        }
+    }
+
+    /// Get the upserted id as `ID`, `None` when nothing is upserted or it is not an object id
+    pub fn upserted(&self) -> Option<ID> {
+        self.upserted_id.as_ref().and_then(as_id)
+    }
+}
+
+impl From<mongodb::results::UpdateResult> for UpdateResult {
+    fn from(value: mongodb::results::UpdateResult) -> Self {
+        Self {
+            matched_count: value.matched_count,
+            modified_count: value.modified_count,
+            upserted_id: value.upserted_id,
+        }
+    }
+}
+
+/// DeleteResult
+///
+/// Represents the result of the delete operations
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DeleteResult {
+    /// Number of documents deleted
+    pub deleted_count: u64,
+}
+
+impl DeleteResult {
+    /// Return `Error::NotFound` when no document is deleted
+    pub fn expect_one(self) -> Result<Self> {
+        if self.deleted_count == 0 {
+            Err(Error::NotFound)
+        } else {
+            Ok(self)
+        }
+    }
+}
+
+impl From<mongodb::results::DeleteResult> for DeleteResult {
+    fn from(value: mongodb::results::DeleteResult) -> Self {
+        Self {
+            deleted_count: value.deleted_count,
+        }
+    }
+}
@@ -0,0 +1,47 @@
+use mangga::{bson::doc, prelude::*};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize, Embedded)]
+#[serde(rename_all = "camelCase")]
+pub struct Address {
+    pub city: String,
+    pub zip_code: String,
+}
+
+#[derive(Debug, Clone, Model, Serialize, Deserialize)]
+#[mangga(name = "users", db = "test")]
+pub struct User {
+    #[serde(rename = "_id")]
+    pub id: ID,
+    pub address: Address,
+    #[serde(rename = "old")]
+    pub previous: Vec<Address>,
+}
+
+fn name<F: Field>(_: F) -> &'static str {
+    F::NAME
+}
+
+#[test]
+fn embedded_fields_use_dotted_paths() {
+    assert_eq!(name(User::ADDRESS.then(Address::CITY)), "address.city");
+    assert_eq!(name(User::PREVIOUS.then(Address::ZIP_CODE)), "old.zipCode");
+}
+
+#[test]
+fn filters_on_embedded_fields() {
+    assert_eq!(
+        User::ADDRESS.then(Address::CITY).eq("Jakarta").as_filter(),
+        doc! { "address.city": { "$eq": "Jakarta" } }
+    );
+}
+
+#[test]
+fn elem_match_on_embedded_elements() {
+    assert_eq!(
+        User::PREVIOUS
+            .elem_match(|address| address.then(Address::ZIP_CODE).eq("40111"))
+            .as_filter(),
+        doc! { "old": { "$elemMatch": { "zipCode": { "$eq": "40111" } } } }
+    );
+}
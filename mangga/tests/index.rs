@@ -0,0 +1,81 @@
+use mangga::prelude::{mongodb::options::Hint, *};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Model, Serialize, Deserialize)]
+#[mangga(name = "users", db = "test")]
+#[index(fields(age = -1, email = 1))]
+#[index(fields(bio = "text"), weights(bio = 5))]
+pub struct User {
+    #[serde(rename = "_id")]
+    pub id: ID,
+    #[index(unique = true)]
+    pub email: String,
+    #[index(score = -1)]
+    pub age: i32,
+    #[index(name = "by-type")]
+    #[index(score = -1)]
+    pub r#type: String,
+    #[index(kind = "hashed")]
+    pub bio: String,
+}
+
+fn hint(index: ModelIndex<User>) -> Hint {
+    index.into()
+}
+
+#[test]
+fn index_constants_use_the_index_name() {
+    assert_eq!(
+        User::INDEX_EMAIL.name(),
+        "mangga_index_email_1_unique_no-exp"
+    );
+    assert_eq!(
+        User::INDEX_AGE_DESC.name(),
+        "mangga_index_age_-1_no-unique_no-exp"
+    );
+    assert_eq!(User::INDEX_BY_TYPE.name(), "by-type");
+    assert_eq!(
+        User::INDEX_TYPE_DESC.name(),
+        "mangga_index_type_-1_no-unique_no-exp"
+    );
+    assert_eq!(
+        User::INDEX_BIO_HASHED.name(),
+        "mangga_index_bio_hashed_no-unique_no-exp"
+    );
+    assert_eq!(
+        User::INDEX_AGE_DESC_EMAIL.name(),
+        "mangga_index_age_-1_email_1_no-unique_no-exp"
+    );
+    assert_eq!(
+        User::INDEX_BIO_TEXT.name(),
+        "mangga_index_bio_text_no-unique_no-exp"
+    );
+}
+
+#[test]
+fn model_index_converts_to_hint() {
+    assert_eq!(hint(ModelIndex::id()), Hint::Name("_id_".to_string()));
+    assert_eq!(
+        hint(User::INDEX_EMAIL),
+        Hint::Name("mangga_index_email_1_unique_no-exp".to_string())
+    );
+}
+
+#[test]
+fn compound_index_keeps_the_declared_key_order() {
+    let spec = User::INDEXES
+        .iter()
+        .find(|spec| spec.name == User::INDEX_AGE_DESC_EMAIL.name())
+        .unwrap();
+    assert_eq!(
+        spec.keys,
+        &[("age", IndexKey::Desc), ("email", IndexKey::Asc)]
+    );
+
+    let text = User::INDEXES
+        .iter()
+        .find(|spec| spec.name == User::INDEX_BIO_TEXT.name())
+        .unwrap();
+    assert_eq!(text.keys, &[("bio", IndexKey::Text)]);
+    assert_eq!(text.weights, &[("bio", 5)]);
+}
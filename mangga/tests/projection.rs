@@ -0,0 +1,36 @@
+use mangga::{bson::doc, prelude::*};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Model, Serialize, Deserialize)]
+#[mangga(name = "users", db = "test")]
+pub struct User {
+    #[serde(rename = "_id")]
+    pub id: ID,
+    pub name: String,
+    pub r#type: String,
+    pub age: i32,
+}
+
+#[derive(Debug, Deserialize, Projection)]
+#[projection(model = User)]
+pub struct UserName {
+    pub name: String,
+    pub r#type: String,
+}
+
+#[derive(Debug, Deserialize, Projection)]
+#[projection(model = User)]
+pub struct UserId {
+    #[serde(rename = "_id")]
+    pub id: ID,
+    pub age: i32,
+}
+
+#[test]
+fn projection_excludes_id_unless_projected() {
+    assert_eq!(
+        UserName::projection(),
+        doc! {"name": 1, "type": 1, "_id": 0}
+    );
+    assert_eq!(UserId::projection(), doc! {"_id": 1, "age": 1});
+}
@@ -0,0 +1,66 @@
+use mangga::{bson::doc, prelude::*};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize, BsonSchema)]
+pub enum Role {
+    Admin,
+    Member,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, BsonSchema)]
+pub struct Address {
+    pub city: String,
+    pub zip: Option<String>,
+}
+
+#[derive(Debug, Clone, Model, Serialize, Deserialize)]
+#[mangga(name = "users", db = "test", schema(level = "moderate"))]
+pub struct User {
+    #[serde(rename = "_id")]
+    pub id: ID,
+    pub r#type: String,
+    pub role: Role,
+    pub address: Option<Address>,
+    pub tags: Vec<String>,
+}
+
+#[test]
+fn derive_lists_only_the_required_fields() {
+    assert_eq!(
+        Address::bson_schema(),
+        doc! {
+            "bsonType": "object",
+            "properties": {
+                "city": { "bsonType": "string" },
+                "zip": { "bsonType": ["string", "null"] },
+            },
+            "required": ["city"],
+        }
+    );
+    assert_eq!(Role::bson_schema(), doc! { "enum": ["Admin", "Member"] });
+}
+
+#[test]
+fn model_schema_uses_the_serde_field_names() {
+    let schema = User::json_schema().unwrap();
+    let properties = schema.get_document("properties").unwrap();
+    assert_eq!(
+        properties.keys().collect::<Vec<_>>(),
+        ["_id", "type", "role", "address", "tags"]
+    );
+    assert_eq!(
+        properties.get_document("address").unwrap(),
+        &doc! {
+            "bsonType": ["object", "null"],
+            "properties": {
+                "city": { "bsonType": "string" },
+                "zip": { "bsonType": ["string", "null"] },
+            },
+            "required": ["city"],
+        }
+    );
+    assert_eq!(
+        schema.get_array("required").unwrap(),
+        &vec!["_id".into(), "type".into(), "role".into(), "tags".into()]
+    );
+}
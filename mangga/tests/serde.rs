@@ -0,0 +1,75 @@
+use mangga::{bson::doc, prelude::*};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Model, Serialize, Deserialize)]
+#[mangga(name = "users", db = "test", schema)]
+#[serde(rename_all = "camelCase")]
+#[index(fields(first_name = 1, last_login = -1))]
+pub struct User {
+    #[serde(rename = "_id")]
+    pub id: ID,
+    pub first_name: String,
+    #[serde(rename = "mail")]
+    pub email: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_login: Option<DateTime>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    #[serde(skip)]
+    pub cache: String,
+}
+
+#[derive(Debug, Deserialize, Projection)]
+#[projection(model = User)]
+#[serde(rename_all = "camelCase")]
+pub struct UserName {
+    pub first_name: String,
+    #[serde(rename = "mail")]
+    pub email: String,
+}
+
+fn name<F: Field>(_: F) -> &'static str {
+    F::NAME
+}
+
+#[test]
+fn field_names_follow_serde() {
+    assert_eq!(name(User::ID), "_id");
+    assert_eq!(name(User::FIRST_NAME), "firstName");
+    assert_eq!(name(User::EMAIL), "mail");
+    assert_eq!(name(User::LAST_LOGIN), "lastLogin");
+    assert_eq!(
+        User::INDEX_FIRST_NAME_LAST_LOGIN_DESC.name(),
+        "mangga_index_firstName_1_lastLogin_-1_no-unique_no-exp"
+    );
+}
+
+#[test]
+fn filters_use_the_stored_keys() {
+    assert_eq!(
+        User::FIRST_NAME.eq("Ann").as_filter(),
+        doc! { "firstName": { "$eq": "Ann" } }
+    );
+}
+
+#[test]
+fn projection_uses_the_stored_keys() {
+    assert_eq!(
+        UserName::projection(),
+        doc! { "firstName": 1, "mail": 1, "_id": 0 }
+    );
+}
+
+#[test]
+fn schema_skips_fields_and_relaxes_optional_ones() {
+    let schema = User::json_schema().unwrap();
+    let properties = schema.get_document("properties").unwrap();
+    assert_eq!(
+        properties.keys().collect::<Vec<_>>(),
+        ["_id", "firstName", "mail", "lastLogin", "tags"]
+    );
+    assert_eq!(
+        schema.get_array("required").unwrap(),
+        &vec!["_id".into(), "firstName".into(), "mail".into()]
+    );
+}